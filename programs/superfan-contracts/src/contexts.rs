@@ -46,6 +46,30 @@ pub struct RegisterSponsor<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct SetSeasonDecay<'info> {
+    #[account(
+        mut,
+        seeds = [b"superfan_config"],
+        bump = config.bump,
+        has_one = admin @ crate::errors::SuperfanError::Unauthorized
+    )]
+    pub config: Account<'info, SuperfanConfig>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct StartSeason<'info> {
+    #[account(
+        mut,
+        seeds = [b"superfan_config"],
+        bump = config.bump,
+        has_one = admin @ crate::errors::SuperfanError::Unauthorized
+    )]
+    pub config: Account<'info, SuperfanConfig>,
+    pub admin: Signer<'info>,
+}
+
 // Market lifecycle
 #[derive(Accounts)]
 #[instruction(market_id: u64)]
@@ -75,6 +99,10 @@ pub struct CreateMarket<'info> {
         space = 8 + Market::SPACE
     )]
     pub market: Account<'info, Market>,
+    /// CHECK: only its key is stored; the seeds tie the market to the
+    /// sponsor's own pool, which may not be funded yet.
+    #[account(seeds = [b"liquidity_pool", sponsor.key().as_ref()], bump)]
+    pub liquidity_pool: UncheckedAccount<'info>,
     pub system_program: Program<'info, System>,
 }
 
@@ -93,6 +121,69 @@ pub struct UpdateMarketStatus<'info> {
     pub market: Account<'info, Market>,
 }
 
+#[derive(Accounts)]
+pub struct SponsorResolveMarket<'info> {
+    #[account(seeds = [b"superfan_config"], bump = config.bump)]
+    pub config: Account<'info, SuperfanConfig>,
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [b"sponsor", authority.key().as_ref()],
+        bump = sponsor.bump,
+        has_one = authority
+    )]
+    pub sponsor: Account<'info, Sponsor>,
+    #[account(mut, seeds = [b"market", sponsor.key().as_ref(), &market.market_id.to_le_bytes()], bump = market.bump)]
+    pub market: Account<'info, Market>,
+    /// CHECK: only checked to be empty; once a signal commitment exists the
+    /// market can only be resolved by proof against it.
+    #[account(seeds = [b"signal", market.key().as_ref()], bump)]
+    pub signal_commit: UncheckedAccount<'info>,
+    /// Required to void the market, which releases its pool reservation.
+    #[account(
+        mut,
+        constraint = liquidity_pool.key() == market.liquidity_pool @ crate::errors::SuperfanError::InvalidStatus
+    )]
+    pub liquidity_pool: Option<Account<'info, LiquidityPool>>,
+}
+
+#[derive(Accounts)]
+pub struct OracleResolveMarket<'info> {
+    #[account(seeds = [b"superfan_config"], bump = config.bump)]
+    pub config: Account<'info, SuperfanConfig>,
+    pub oracle: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"market", market.sponsor.as_ref(), &market.market_id.to_le_bytes()],
+        bump = market.bump,
+        constraint = market.signal_oracle == oracle.key() @ crate::errors::SuperfanError::Unauthorized
+    )]
+    pub market: Account<'info, Market>,
+    /// Required to void the market, which releases its pool reservation.
+    #[account(
+        mut,
+        constraint = liquidity_pool.key() == market.liquidity_pool @ crate::errors::SuperfanError::InvalidStatus
+    )]
+    pub liquidity_pool: Option<Account<'info, LiquidityPool>>,
+}
+
+#[derive(Accounts)]
+pub struct VoidExpiredMarket<'info> {
+    #[account(seeds = [b"superfan_config"], bump = config.bump)]
+    pub config: Account<'info, SuperfanConfig>,
+    pub caller: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"market", market.sponsor.as_ref(), &market.market_id.to_le_bytes()],
+        bump = market.bump
+    )]
+    pub market: Account<'info, Market>,
+    #[account(
+        mut,
+        constraint = liquidity_pool.key() == market.liquidity_pool @ crate::errors::SuperfanError::InvalidStatus
+    )]
+    pub liquidity_pool: Account<'info, LiquidityPool>,
+}
+
 // Liquidity
 #[derive(Accounts)]
 pub struct FundPool<'info> {
@@ -166,6 +257,21 @@ pub struct WithdrawPool<'info> {
     pub associated_token_program: Program<'info, anchor_spl::associated_token::AssociatedToken>,
 }
 
+#[derive(Accounts)]
+pub struct ConfigureReputationBonus<'info> {
+    #[account(seeds = [b"superfan_config"], bump = config.bump)]
+    pub config: Account<'info, SuperfanConfig>,
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [b"sponsor", authority.key().as_ref()],
+        bump = sponsor.bump,
+        has_one = authority
+    )]
+    pub sponsor: Account<'info, Sponsor>,
+    #[account(mut, seeds = [b"liquidity_pool", sponsor.key().as_ref()], bump = liquidity_pool.bump)]
+    pub liquidity_pool: Account<'info, LiquidityPool>,
+}
+
 // Positions
 #[derive(Accounts)]
 pub struct OpenPosition<'info> {
@@ -176,6 +282,7 @@ pub struct OpenPosition<'info> {
     #[account(address = config.usdc_mint)]
     pub mint: Account<'info, anchor_spl::token::Mint>,
     #[account(
+        mut,
         seeds = [b"market", market.sponsor.as_ref(), &market.market_id.to_le_bytes()],
         bump = market.bump
     )]
@@ -205,11 +312,70 @@ pub struct OpenPosition<'info> {
         space = 8 + Position::SPACE
     )]
     pub position: Account<'info, Position>,
+    /// Required when the market is reputation-gated or in its early-access window.
+    #[account(
+        mut,
+        seeds = [b"scout", user.key().as_ref()],
+        bump = scout_registry.bump,
+        has_one = user
+    )]
+    pub scout_registry: Option<Account<'info, ScoutRegistry>>,
     pub token_program: Program<'info, anchor_spl::token::Token>,
     pub associated_token_program: Program<'info, anchor_spl::associated_token::AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct AdjustPosition<'info> {
+    #[account(seeds = [b"superfan_config"], bump = config.bump)]
+    pub config: Account<'info, SuperfanConfig>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(address = config.usdc_mint)]
+    pub mint: Account<'info, anchor_spl::token::Mint>,
+    #[account(
+        mut,
+        seeds = [b"market", market.sponsor.as_ref(), &market.market_id.to_le_bytes()],
+        bump = market.bump
+    )]
+    pub market: Account<'info, Market>,
+    #[account(
+        mut,
+        constraint = liquidity_pool.key() == market.liquidity_pool @ crate::errors::SuperfanError::InvalidStatus
+    )]
+    pub liquidity_pool: Account<'info, LiquidityPool>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = liquidity_pool
+    )]
+    pub liquidity_vault: Account<'info, anchor_spl::token::TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = user
+    )]
+    pub user_token_account: Account<'info, anchor_spl::token::TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"position", market.key().as_ref(), user.key().as_ref()],
+        bump = position.bump,
+        has_one = user,
+        has_one = market
+    )]
+    pub position: Account<'info, Position>,
+    /// Required to add stake to a reputation-gated or early-access market.
+    #[account(
+        mut,
+        seeds = [b"scout", user.key().as_ref()],
+        bump = scout_registry.bump,
+        has_one = user
+    )]
+    pub scout_registry: Option<Account<'info, ScoutRegistry>>,
+    pub token_program: Program<'info, anchor_spl::token::Token>,
+    pub associated_token_program: Program<'info, anchor_spl::associated_token::AssociatedToken>,
+}
+
 #[derive(Accounts)]
 pub struct ClosePosition<'info> {
     #[account(seeds = [b"superfan_config"], bump = config.bump)]
@@ -219,6 +385,7 @@ pub struct ClosePosition<'info> {
     #[account(address = config.usdc_mint)]
     pub mint: Account<'info, anchor_spl::token::Mint>,
     #[account(
+        mut,
         seeds = [b"market", market.sponsor.as_ref(), &market.market_id.to_le_bytes()],
         bump = market.bump
     )]
@@ -249,41 +416,326 @@ pub struct ClosePosition<'info> {
         has_one = market
     )]
     pub position: Account<'info, Position>,
+    /// CHECK: the holder's `scout` PDA, read as their registry if they
+    /// created one; holders without one settle without reputation.
+    #[account(mut, seeds = [b"scout", user.key().as_ref()], bump)]
+    pub scout_registry: UncheckedAccount<'info>,
+    pub token_program: Program<'info, anchor_spl::token::Token>,
+    pub associated_token_program: Program<'info, anchor_spl::associated_token::AssociatedToken>,
+}
+
+#[derive(Accounts)]
+pub struct TransferPosition<'info> {
+    #[account(seeds = [b"superfan_config"], bump = config.bump)]
+    pub config: Account<'info, SuperfanConfig>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    /// CHECK: any wallet can receive a position; only its key is used.
+    pub recipient: UncheckedAccount<'info>,
+    #[account(
+        seeds = [b"market", market.sponsor.as_ref(), &market.market_id.to_le_bytes()],
+        bump = market.bump
+    )]
+    pub market: Account<'info, Market>,
+    #[account(
+        mut,
+        close = user,
+        seeds = [b"position", market.key().as_ref(), user.key().as_ref()],
+        bump = position.bump,
+        has_one = user,
+        has_one = market
+    )]
+    pub position: Account<'info, Position>,
+    #[account(
+        init,
+        seeds = [b"position", market.key().as_ref(), recipient.key().as_ref()],
+        bump,
+        payer = user,
+        space = 8 + Position::SPACE
+    )]
+    pub recipient_position: Account<'info, Position>,
+    /// The recipient's registry, required when the market is reputation-gated
+    /// or in its early-access window.
+    #[account(
+        mut,
+        seeds = [b"scout", recipient.key().as_ref()],
+        bump = recipient_scout_registry.bump,
+        constraint = recipient_scout_registry.user == recipient.key() @ crate::errors::SuperfanError::Unauthorized
+    )]
+    pub recipient_scout_registry: Option<Account<'info, ScoutRegistry>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimWinnings<'info> {
+    #[account(seeds = [b"superfan_config"], bump = config.bump)]
+    pub config: Account<'info, SuperfanConfig>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(address = config.usdc_mint)]
+    pub mint: Account<'info, anchor_spl::token::Mint>,
+    #[account(
+        mut,
+        seeds = [b"market", market.sponsor.as_ref(), &market.market_id.to_le_bytes()],
+        bump = market.bump
+    )]
+    pub market: Account<'info, Market>,
+    #[account(
+        mut,
+        constraint = liquidity_pool.key() == market.liquidity_pool @ crate::errors::SuperfanError::InvalidStatus
+    )]
+    pub liquidity_pool: Account<'info, LiquidityPool>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = liquidity_pool
+    )]
+    pub liquidity_vault: Account<'info, anchor_spl::token::TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = user
+    )]
+    pub user_token_account: Account<'info, anchor_spl::token::TokenAccount>,
+    #[account(
+        mut,
+        close = user,
+        seeds = [b"position", market.key().as_ref(), user.key().as_ref()],
+        bump = position.bump,
+        has_one = user,
+        has_one = market
+    )]
+    pub position: Account<'info, Position>,
+    /// CHECK: the holder's `scout` PDA, read as their registry if they
+    /// created one; holders without one settle without reputation.
+    #[account(mut, seeds = [b"scout", user.key().as_ref()], bump)]
+    pub scout_registry: UncheckedAccount<'info>,
+    pub token_program: Program<'info, anchor_spl::token::Token>,
+    pub associated_token_program: Program<'info, anchor_spl::associated_token::AssociatedToken>,
+}
+
+// Outcome tokens
+#[derive(Accounts)]
+pub struct CreateOutcomeMints<'info> {
+    #[account(seeds = [b"superfan_config"], bump = config.bump)]
+    pub config: Account<'info, SuperfanConfig>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [b"sponsor", authority.key().as_ref()],
+        bump = sponsor.bump,
+        has_one = authority
+    )]
+    pub sponsor: Account<'info, Sponsor>,
+    #[account(
+        mut,
+        seeds = [b"market", sponsor.key().as_ref(), &market.market_id.to_le_bytes()],
+        bump = market.bump
+    )]
+    pub market: Account<'info, Market>,
+    #[account(address = config.usdc_mint)]
+    pub mint: Account<'info, anchor_spl::token::Mint>,
+    #[account(
+        init,
+        seeds = [b"yes_mint", market.key().as_ref()],
+        bump,
+        payer = authority,
+        mint::decimals = mint.decimals,
+        mint::authority = market
+    )]
+    pub yes_mint: Account<'info, anchor_spl::token::Mint>,
+    #[account(
+        init,
+        seeds = [b"no_mint", market.key().as_ref()],
+        bump,
+        payer = authority,
+        mint::decimals = mint.decimals,
+        mint::authority = market
+    )]
+    pub no_mint: Account<'info, anchor_spl::token::Mint>,
+    pub token_program: Program<'info, anchor_spl::token::Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct BuyOutcomeTokens<'info> {
+    #[account(seeds = [b"superfan_config"], bump = config.bump)]
+    pub config: Account<'info, SuperfanConfig>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(address = config.usdc_mint)]
+    pub mint: Account<'info, anchor_spl::token::Mint>,
+    #[account(
+        mut,
+        seeds = [b"market", market.sponsor.as_ref(), &market.market_id.to_le_bytes()],
+        bump = market.bump
+    )]
+    pub market: Account<'info, Market>,
+    #[account(
+        mut,
+        constraint = liquidity_pool.key() == market.liquidity_pool @ crate::errors::SuperfanError::InvalidStatus
+    )]
+    pub liquidity_pool: Account<'info, LiquidityPool>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = liquidity_pool
+    )]
+    pub liquidity_vault: Account<'info, anchor_spl::token::TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = user
+    )]
+    pub user_token_account: Account<'info, anchor_spl::token::TokenAccount>,
+    /// Checked against `market.yes_mint` / `market.no_mint` in the handler.
+    #[account(mut)]
+    pub outcome_mint: Account<'info, anchor_spl::token::Mint>,
+    #[account(
+        mut,
+        associated_token::mint = outcome_mint,
+        associated_token::authority = user
+    )]
+    pub user_outcome_account: Account<'info, anchor_spl::token::TokenAccount>,
+    /// Required when the market is reputation-gated or in its early-access window.
+    #[account(
+        mut,
+        seeds = [b"scout", user.key().as_ref()],
+        bump = scout_registry.bump,
+        has_one = user
+    )]
+    pub scout_registry: Option<Account<'info, ScoutRegistry>>,
+    pub token_program: Program<'info, anchor_spl::token::Token>,
+    pub associated_token_program: Program<'info, anchor_spl::associated_token::AssociatedToken>,
+}
+
+#[derive(Accounts)]
+pub struct RedeemOutcomeTokens<'info> {
+    #[account(seeds = [b"superfan_config"], bump = config.bump)]
+    pub config: Account<'info, SuperfanConfig>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(address = config.usdc_mint)]
+    pub mint: Account<'info, anchor_spl::token::Mint>,
+    #[account(
+        mut,
+        seeds = [b"market", market.sponsor.as_ref(), &market.market_id.to_le_bytes()],
+        bump = market.bump
+    )]
+    pub market: Account<'info, Market>,
+    #[account(
+        mut,
+        constraint = liquidity_pool.key() == market.liquidity_pool @ crate::errors::SuperfanError::InvalidStatus
+    )]
+    pub liquidity_pool: Account<'info, LiquidityPool>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = liquidity_pool
+    )]
+    pub liquidity_vault: Account<'info, anchor_spl::token::TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = user
+    )]
+    pub user_token_account: Account<'info, anchor_spl::token::TokenAccount>,
+    /// Checked against `market.yes_mint` / `market.no_mint` in the handler.
+    #[account(mut)]
+    pub outcome_mint: Account<'info, anchor_spl::token::Mint>,
+    #[account(
+        mut,
+        associated_token::mint = outcome_mint,
+        associated_token::authority = user
+    )]
+    pub user_outcome_account: Account<'info, anchor_spl::token::TokenAccount>,
     pub token_program: Program<'info, anchor_spl::token::Token>,
     pub associated_token_program: Program<'info, anchor_spl::associated_token::AssociatedToken>,
 }
 
 // Reputation
 #[derive(Accounts)]
-pub struct UpdateReputation<'info> {
+pub struct InitScout<'info> {
     #[account(seeds = [b"superfan_config"], bump = config.bump)]
     pub config: Account<'info, SuperfanConfig>,
     #[account(mut)]
-    pub admin: Signer<'info>,
+    pub payer: Signer<'info>,
     /// CHECK: user identity tracked off-chain; signature not required here
     pub user: UncheckedAccount<'info>,
     #[account(
         init,
         seeds = [b"scout", user.key().as_ref()],
         bump,
-        payer = admin,
+        payer = payer,
         space = 8 + ScoutRegistry::SPACE
     )]
     pub scout_registry: Account<'info, ScoutRegistry>,
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct UpdateReputation<'info> {
+    #[account(seeds = [b"superfan_config"], bump = config.bump)]
+    pub config: Account<'info, SuperfanConfig>,
+    pub admin: Signer<'info>,
+    /// CHECK: user identity tracked off-chain; signature not required here
+    pub user: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"scout", user.key().as_ref()],
+        bump = scout_registry.bump,
+        has_one = user
+    )]
+    pub scout_registry: Account<'info, ScoutRegistry>,
+}
+
+#[derive(Accounts)]
+pub struct ReadScout<'info> {
+    #[account(
+        seeds = [b"scout", scout_registry.user.as_ref()],
+        bump = scout_registry.bump
+    )]
+    pub scout_registry: Account<'info, ScoutRegistry>,
+}
+
+#[derive(Accounts)]
+pub struct RollScoutSeason<'info> {
+    #[account(seeds = [b"superfan_config"], bump = config.bump)]
+    pub config: Account<'info, SuperfanConfig>,
+    #[account(
+        mut,
+        seeds = [b"scout", scout_registry.user.as_ref()],
+        bump = scout_registry.bump
+    )]
+    pub scout_registry: Account<'info, ScoutRegistry>,
+}
+
 // Payouts
 #[derive(Accounts)]
 pub struct DistributePayouts<'info> {
     #[account(seeds = [b"superfan_config"], bump = config.bump)]
     pub config: Account<'info, SuperfanConfig>,
-    pub admin: Signer<'info>,
+    pub cranker: Signer<'info>,
+    #[account(address = config.usdc_mint)]
+    pub mint: Account<'info, anchor_spl::token::Mint>,
     #[account(
+        mut,
         seeds = [b"market", market.sponsor.as_ref(), &market.market_id.to_le_bytes()],
         bump = market.bump
     )]
     pub market: Account<'info, Market>,
+    #[account(
+        mut,
+        constraint = liquidity_pool.key() == market.liquidity_pool @ crate::errors::SuperfanError::InvalidStatus
+    )]
+    pub liquidity_pool: Account<'info, LiquidityPool>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = liquidity_pool
+    )]
+    pub liquidity_vault: Account<'info, anchor_spl::token::TokenAccount>,
+    pub token_program: Program<'info, anchor_spl::token::Token>,
 }
 
 // Signal commit
@@ -314,3 +766,35 @@ pub struct SubmitSignalCommitment<'info> {
     pub signal_commit: Account<'info, SignalCommitment>,
     pub system_program: Program<'info, System>,
 }
+
+#[derive(Accounts)]
+pub struct VerifySignal<'info> {
+    #[account(
+        seeds = [b"market", market.sponsor.as_ref(), &market.market_id.to_le_bytes()],
+        bump = market.bump
+    )]
+    pub market: Account<'info, Market>,
+    #[account(
+        seeds = [b"signal", market.key().as_ref()],
+        bump = signal_commit.bump
+    )]
+    pub signal_commit: Account<'info, SignalCommitment>,
+}
+
+#[derive(Accounts)]
+pub struct ProofResolveMarket<'info> {
+    #[account(seeds = [b"superfan_config"], bump = config.bump)]
+    pub config: Account<'info, SuperfanConfig>,
+    pub resolver: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"market", market.sponsor.as_ref(), &market.market_id.to_le_bytes()],
+        bump = market.bump
+    )]
+    pub market: Account<'info, Market>,
+    #[account(
+        seeds = [b"signal", market.key().as_ref()],
+        bump = signal_commit.bump
+    )]
+    pub signal_commit: Account<'info, SignalCommitment>,
+}
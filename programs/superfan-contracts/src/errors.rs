@@ -22,8 +22,48 @@ pub enum SuperfanError {
     InvalidStatus,
     #[msg("Resolution deadline passed")]
     ResolutionDeadlinePassed,
+    #[msg("Resolution deadline not reached")]
+    ResolutionDeadlineNotReached,
     #[msg("Invalid amount")]
     InvalidAmount,
     #[msg("Insufficient liquidity")]
     InsufficientLiquidity,
+    #[msg("Position already settled")]
+    PositionAlreadySettled,
+    #[msg("Invalid payout accounts")]
+    InvalidPayoutAccounts,
+    #[msg("Basis points out of range")]
+    InvalidBps,
+    #[msg("Trading window closed")]
+    TradingClosed,
+    #[msg("Trading has not started")]
+    TradingNotStarted,
+    #[msg("Market pool exposure limit reached")]
+    MarketExposureExceeded,
+    #[msg("Market must be resolved by its signal oracle")]
+    OracleResolutionRequired,
+    #[msg("Signal proof does not match commitment")]
+    InvalidSignalProof,
+    #[msg("Market has a signal commitment and must be resolved with a proof")]
+    ProofResolutionRequired,
+    #[msg("Scout registry account required")]
+    ScoutRegistryRequired,
+    #[msg("Reputation too low for this market")]
+    ReputationTooLow,
+    #[msg("Unknown market kind")]
+    InvalidMarketKind,
+    #[msg("Order expired before execution")]
+    OrderExpired,
+    #[msg("Fill below the requested minimum")]
+    SlippageExceeded,
+    #[msg("Outcome mint does not match this market or outcome")]
+    InvalidOutcomeMint,
+    #[msg("Invalid outcome for this market")]
+    InvalidOutcome,
+    #[msg("Scalar upper bound must exceed lower bound")]
+    InvalidScalarRange,
+    #[msg("Liquidity pool account required")]
+    LiquidityPoolRequired,
+    #[msg("Season decay rate is fixed once the first season starts")]
+    SeasonDecayLocked,
 }
@@ -4,26 +4,121 @@ use anchor_lang::prelude::*;
 pub struct MarketResolved {
     pub market: Pubkey,
     pub sponsor: Pubkey,
-    pub outcome_yes: bool,
+    pub outcome: u8,
     pub resolved_at: i64,
 }
 
+#[event]
+pub struct PoolWithdrawn {
+    pub liquidity_pool: Pubkey,
+    pub sponsor: Pubkey,
+    pub amount: u64,
+    pub remaining_capacity: u64,
+}
+
 #[event]
 pub struct PositionOpened {
     pub market: Pubkey,
     pub user: Pubkey,
+    pub outcome: u8,
+    pub amount: u64,
+    pub shares: u64,
+    pub implied_prob_bps: u16, // price of `outcome` after the trade
+    pub early_supporter: bool,
+}
+
+#[event]
+pub struct PositionIncreased {
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub shares: u64,
+    pub implied_prob_bps: u16,
+}
+
+#[event]
+pub struct PositionDecreased {
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub payout: u64,
+}
+
+#[event]
+pub struct PositionTransferred {
+    pub market: Pubkey,
+    pub from: Pubkey,
+    pub to: Pubkey,
     pub amount: u64,
 }
 
+#[event]
+pub struct PositionClosed {
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub payout: u64,
+}
+
+#[event]
+pub struct PositionSettled {
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub payout: u64,
+}
+
 #[event]
 pub struct ReputationUpdated {
     pub user: Pubkey,
     pub new_score: i64,
+    pub season: u32,
+    pub season_score: i64,
+}
+
+#[event]
+pub struct SeasonStarted {
+    pub season: u32,
+    pub started_at: i64,
+    pub decay_bps: u16,
+}
+
+#[event]
+pub struct ScoutSeasonClosed {
+    pub user: Pubkey,
+    pub season: u32,
+    pub season_score: i64,
 }
 
 #[event]
 pub struct SignalCommitted {
     pub market: Pubkey,
     pub commitment_root: [u8; 32],
+    pub resolution_metric: [u8; 32],
     pub recorded_at: i64,
 }
+
+#[event]
+pub struct SignalVerified {
+    pub market: Pubkey,
+    pub subject: Pubkey,
+    pub metric: [u8; 32],
+    pub value: i64,
+}
+
+#[event]
+pub struct OutcomeTokensBought {
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub side_yes: bool,
+    pub amount: u64,
+    pub shares: u64,
+    pub implied_prob_bps: u16,
+}
+
+#[event]
+pub struct OutcomeTokensRedeemed {
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub side_yes: bool,
+    pub amount: u64,
+    pub payout: u64,
+}
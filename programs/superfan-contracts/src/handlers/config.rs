@@ -2,6 +2,8 @@ use anchor_lang::prelude::*;
 
 use crate::contexts::*;
 use crate::errors::SuperfanError;
+use crate::events::SeasonStarted;
+use crate::state::BPS_DENOMINATOR;
 
 pub fn initialize_config(
     ctx: Context<InitializeConfig>,
@@ -13,6 +15,9 @@ pub fn initialize_config(
     config.admin = admin;
     config.usdc_mint = usdc_mint;
     config.max_sponsors = max_sponsors;
+    config.current_season = 0;
+    config.season_started_at = Clock::get()?.unix_timestamp;
+    config.season_decay_bps = 0;
     config.bump = ctx.bumps.config;
     Ok(())
 }
@@ -36,3 +41,31 @@ pub fn register_sponsor(ctx: Context<RegisterSponsor>, name_hash: [u8; 32]) -> R
 
     Ok(())
 }
+
+/// Sets how much lifetime scores fade for every season a registry skips. Only
+/// allowed before the first season, since lagging registries apply the rate
+/// to every season they missed.
+pub fn set_season_decay(ctx: Context<SetSeasonDecay>, decay_bps: u16) -> Result<()> {
+    require!(decay_bps as u64 <= BPS_DENOMINATOR, SuperfanError::InvalidBps);
+    let config = &mut ctx.accounts.config;
+    require!(config.current_season == 0, SuperfanError::SeasonDecayLocked);
+    config.season_decay_bps = decay_bps;
+    Ok(())
+}
+
+/// Closes the current leaderboard season. Scout registries roll over lazily
+/// the next time they are touched, decaying by `season_decay_bps`.
+pub fn start_season(ctx: Context<StartSeason>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.current_season = config
+        .current_season
+        .checked_add(1)
+        .ok_or(SuperfanError::MathOverflow)?;
+    config.season_started_at = Clock::get()?.unix_timestamp;
+    emit!(SeasonStarted {
+        season: config.current_season,
+        started_at: config.season_started_at,
+        decay_bps: config.season_decay_bps,
+    });
+    Ok(())
+}
@@ -1,20 +1,32 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Transfer};
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
 
 use crate::contexts::*;
 use crate::errors::SuperfanError;
+use crate::events::PoolWithdrawn;
+use crate::state::{LiquidityPool, Market, BPS_DENOMINATOR};
 
 pub fn fund_pool(
     ctx: Context<FundPool>,
     capacity: u64,
     first_n_limit: u16,
+    early_bonus_bps: u16,
+    early_min_stake: u64,
 ) -> Result<()> {
     require!(capacity > 0, SuperfanError::InvalidAmount);
+    require!(
+        early_bonus_bps as u64 <= BPS_DENOMINATOR,
+        SuperfanError::InvalidBps
+    );
     let pool = &mut ctx.accounts.liquidity_pool;
     pool.sponsor = ctx.accounts.sponsor.key();
     pool.capacity = capacity;
     pool.locked = 0;
     pool.first_n_limit = first_n_limit;
+    pool.early_bonus_bps = early_bonus_bps;
+    pool.early_min_stake = early_min_stake;
+    pool.reputation_bonus_budget = 0;
+    pool.reputation_bonus_max_bps = 0;
     pool.vault = ctx.accounts.liquidity_vault.key();
     pool.bump = ctx.bumps.liquidity_pool;
 
@@ -29,10 +41,90 @@ pub fn fund_pool(
     Ok(())
 }
 
-pub fn withdraw_pool(_ctx: Context<WithdrawPool>) -> Result<()> {
-    // Placeholder: token movements to be implemented with a proper vault.
-    // Require that nothing is locked before withdrawal.
-    // let pool = &ctx.accounts.liquidity_pool;
-    // require!(pool.locked == 0, SuperfanError::InvalidStatus);
+pub fn withdraw_pool(ctx: Context<WithdrawPool>, amount: u64) -> Result<()> {
+    require!(amount > 0, SuperfanError::InvalidAmount);
+    let pool = &mut ctx.accounts.liquidity_pool;
+    // Only capital not backing open positions can leave; once every market on
+    // the pool is settled `locked` is zero and the full capacity is free.
+    require!(amount <= pool.available(), SuperfanError::InsufficientLiquidity);
+    pool.capacity -= amount;
+
+    transfer_from_vault(
+        &ctx.accounts.token_program,
+        &ctx.accounts.liquidity_pool,
+        &ctx.accounts.liquidity_vault,
+        ctx.accounts.sponsor_token_account.to_account_info(),
+        amount,
+    )?;
+
+    let pool = &ctx.accounts.liquidity_pool;
+    emit!(PoolWithdrawn {
+        liquidity_pool: pool.key(),
+        sponsor: pool.sponsor,
+        amount,
+        remaining_capacity: pool.capacity,
+    });
+    Ok(())
+}
+
+/// Sets aside part of the pool's free capital for tiered scout bonuses paid on
+/// top of winnings. Lowering the budget returns the difference to `available`.
+pub fn configure_reputation_bonus(
+    ctx: Context<ConfigureReputationBonus>,
+    budget: u64,
+    max_bonus_bps: u16,
+) -> Result<()> {
+    require!(
+        max_bonus_bps as u64 <= BPS_DENOMINATOR,
+        SuperfanError::InvalidBps
+    );
+    let pool = &mut ctx.accounts.liquidity_pool;
+    require!(
+        budget <= pool.capacity.saturating_sub(pool.locked),
+        SuperfanError::InsufficientLiquidity
+    );
+    pool.reputation_bonus_budget = budget;
+    pool.reputation_bonus_max_bps = max_bonus_bps;
     Ok(())
 }
+
+/// Re-reserves pool capital to match the market's current liability. Growth
+/// must fit in the pool's free capital; shrinkage returns capital to it.
+pub(crate) fn sync_exposure(pool: &mut LiquidityPool, market: &mut Market) -> Result<()> {
+    let liability = market.liability();
+    if liability > market.pool_exposure {
+        let increase = liability - market.pool_exposure;
+        require!(increase <= pool.available(), SuperfanError::InsufficientLiquidity);
+        pool.locked += increase;
+    } else {
+        pool.locked = pool
+            .locked
+            .saturating_sub(market.pool_exposure - liability);
+    }
+    market.pool_exposure = liability;
+    Ok(())
+}
+
+/// Moves `amount` out of the pool vault, signed by the `liquidity_pool` PDA.
+pub(crate) fn transfer_from_vault<'info>(
+    token_program: &Program<'info, Token>,
+    liquidity_pool: &Account<'info, LiquidityPool>,
+    liquidity_vault: &Account<'info, TokenAccount>,
+    to: AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    let seeds: &[&[u8]] = &[
+        b"liquidity_pool",
+        liquidity_pool.sponsor.as_ref(),
+        &[liquidity_pool.bump],
+    ];
+    let signer = &[seeds];
+    let cpi_accounts = Transfer {
+        from: liquidity_vault.to_account_info(),
+        to,
+        authority: liquidity_pool.to_account_info(),
+    };
+    let cpi_ctx =
+        CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer);
+    token::transfer(cpi_ctx, amount)
+}
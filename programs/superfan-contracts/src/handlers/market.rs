@@ -3,7 +3,12 @@ use anchor_lang::prelude::*;
 use crate::contexts::*;
 use crate::errors::SuperfanError;
 use crate::events::MarketResolved;
-use crate::state::MarketStatus;
+use crate::handlers::liquidity::sync_exposure;
+use crate::lmsr;
+use crate::state::{
+    LiquidityPool, Market, MarketKind, MarketOutcome, MarketStatus, BPS_DENOMINATOR,
+    MAX_OUTCOMES,
+};
 
 #[allow(clippy::too_many_arguments)]
 pub fn create_market(
@@ -16,8 +21,16 @@ pub fn create_market(
     resolution_deadline: i64,
     conviction_threshold_bps: u16,
     max_pool_exposure: u64,
-    liquidity_pool: Pubkey,
     signal_oracle: Pubkey,
+    early_exit_penalty_bps: u16,
+    min_reputation: Option<i64>,
+    early_access_starts_at: Option<i64>,
+    early_access_min_reputation: i64,
+    market_kind: u8,
+    fee_bps: u16,
+    outcome_count: u8,
+    scalar_lower: u64,
+    scalar_upper: u64,
 ) -> Result<()> {
     require!(
         trading_ends_at > trading_starts_at,
@@ -27,6 +40,47 @@ pub fn create_market(
         resolution_deadline > trading_ends_at,
         SuperfanError::InvalidResolutionDeadline
     );
+    require!(
+        early_exit_penalty_bps as u64 <= BPS_DENOMINATOR,
+        SuperfanError::InvalidBps
+    );
+    require!(fee_bps as u64 <= BPS_DENOMINATOR, SuperfanError::InvalidBps);
+    require!(
+        (2..=MAX_OUTCOMES as u8).contains(&outcome_count),
+        SuperfanError::InvalidOutcome
+    );
+    // The LMSR subsidy is sized so the pool's worst-case loss is the exposure
+    // cap; parimutuel markets have no subsidy to size.
+    let lmsr_b = if market_kind == MarketKind::SponsorBacked as u8 {
+        let lmsr_b = lmsr::liquidity_for_exposure(max_pool_exposure, outcome_count);
+        require!(lmsr_b > 0, SuperfanError::InvalidAmount);
+        lmsr_b
+    } else {
+        require!(
+            market_kind == MarketKind::Parimutuel as u8,
+            SuperfanError::InvalidMarketKind
+        );
+        0
+    };
+    // Scalar markets are LONG/SHORT sponsor-backed markets settled only by
+    // their oracle's reported value.
+    if scalar_lower > 0 || scalar_upper > 0 {
+        require!(scalar_upper > scalar_lower, SuperfanError::InvalidScalarRange);
+        require!(
+            market_kind == MarketKind::SponsorBacked as u8 && outcome_count == 2,
+            SuperfanError::InvalidMarketKind
+        );
+        require!(
+            signal_oracle != Pubkey::default(),
+            SuperfanError::OracleResolutionRequired
+        );
+    }
+    if let Some(early_access_starts_at) = early_access_starts_at {
+        require!(
+            early_access_starts_at < trading_starts_at,
+            SuperfanError::InvalidTradingWindow
+        );
+    }
 
     let counter = &mut ctx.accounts.market_counter;
     require!(market_id == counter.next_market_id, SuperfanError::InvalidMarketId);
@@ -45,11 +99,38 @@ pub fn create_market(
     market.resolution_deadline = resolution_deadline;
     market.conviction_threshold_bps = conviction_threshold_bps;
     market.max_pool_exposure = max_pool_exposure;
-    market.liquidity_pool = liquidity_pool;
+    market.liquidity_pool = ctx.accounts.liquidity_pool.key();
     market.signal_oracle = signal_oracle;
+    market.early_exit_penalty_bps = early_exit_penalty_bps;
+    market.min_reputation = min_reputation;
+    market.early_access_starts_at = early_access_starts_at;
+    market.early_access_min_reputation = early_access_min_reputation;
+    market.market_kind = market_kind;
+    market.fee_bps = fee_bps;
+    market.outcome_count = outcome_count;
     market.status = MarketStatus::Pending as u8;
-    market.outcome = 0;
+    market.outcome = MarketOutcome::Unresolved as u8;
     market.resolved_at = 0;
+    market.outcome_totals = [0; MAX_OUTCOMES];
+    market.pool_exposure = 0;
+    market.measured_bps = 0;
+    market.early_supporter_count = 0;
+    market.lmsr_b = lmsr_b;
+    market.outcome_shares = [0; MAX_OUTCOMES];
+    market.outcome_payouts = [0; MAX_OUTCOMES];
+    // Every outcome starts equally likely.
+    market.implied_probs_bps = [0; MAX_OUTCOMES];
+    market.implied_probs_bps[..outcome_count as usize]
+        .fill((BPS_DENOMINATOR / outcome_count as u64) as u16);
+    market.stakes_released = false;
+    market.yes_mint = Pubkey::default();
+    market.no_mint = Pubkey::default();
+    market.yes_token_stake = 0;
+    market.no_token_stake = 0;
+    market.scalar_lower = scalar_lower;
+    market.scalar_upper = scalar_upper;
+    market.scalar_value = 0;
+    market.long_payout_bps = 0;
     market.bump = ctx.bumps.market;
 
     Ok(())
@@ -79,9 +160,127 @@ pub fn cancel_market(ctx: Context<UpdateMarketStatus>) -> Result<()> {
     Ok(())
 }
 
-pub fn resolve_market(ctx: Context<UpdateMarketStatus>, outcome_yes: bool) -> Result<()> {
+/// Declares the winning outcome, `1..=outcome_count` (1 = YES, 2 = NO on a
+/// binary market), or `MarketOutcome::Invalid` to void the market. Sponsors
+/// can only void while trading is open, before the result is known, and
+/// once they commit signal data the market follows its proof instead.
+pub fn resolve_market(ctx: Context<SponsorResolveMarket>, outcome: u8) -> Result<()> {
     let market = &mut ctx.accounts.market;
+    require_no_signal_oracle(market)?;
+    require!(
+        ctx.accounts.signal_commit.data_is_empty(),
+        SuperfanError::ProofResolutionRequired
+    );
+    if outcome == MarketOutcome::Invalid as u8 {
+        let now = Clock::get()?.unix_timestamp;
+        require!(now < market.trading_ends_at, SuperfanError::TradingClosed);
+        return void_market(market, ctx.accounts.liquidity_pool.as_deref_mut());
+    }
+    apply_resolution(market, outcome)
+}
+
+/// Lets a market's signal oracle void it when its own data can't be trusted.
+pub fn void_market_by_oracle(ctx: Context<OracleResolveMarket>) -> Result<()> {
+    void_market(
+        &mut ctx.accounts.market,
+        ctx.accounts.liquidity_pool.as_deref_mut(),
+    )
+}
+
+/// Lets anyone void a market that missed its `resolution_deadline`, so stakes
+/// are never stuck waiting on a sponsor or oracle that does not resolve.
+pub fn void_expired_market(ctx: Context<VoidExpiredMarket>) -> Result<()> {
     let now = Clock::get()?.unix_timestamp;
+    require!(
+        now > ctx.accounts.market.resolution_deadline,
+        SuperfanError::ResolutionDeadlineNotReached
+    );
+    void_market(&mut ctx.accounts.market, Some(&mut ctx.accounts.liquidity_pool))
+}
+
+/// Resolves from the oracle's measured metric: YES iff it meets the market's
+/// `conviction_threshold_bps`. Binary markets only. Signed by the market's
+/// `signal_oracle` so the sponsor funding the payouts cannot pick the outcome.
+pub fn resolve_market_by_oracle(
+    ctx: Context<OracleResolveMarket>,
+    measured_bps: i64,
+) -> Result<()> {
+    resolve_from_signal(&mut ctx.accounts.market, measured_bps)
+}
+
+/// Settles a scalar market from the oracle's reported value, clamped to the
+/// market's range: LONG holders receive `(value - lower) / (upper - lower)` of
+/// their face value and SHORT holders the rest.
+pub fn resolve_scalar_market(ctx: Context<OracleResolveMarket>, value: u64) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    require!(market.is_scalar(), SuperfanError::InvalidMarketKind);
+    let value = value.clamp(market.scalar_lower, market.scalar_upper);
+    let long_payout_bps = (value - market.scalar_lower) as u128 * BPS_DENOMINATOR as u128
+        / (market.scalar_upper - market.scalar_lower) as u128;
+    market.scalar_value = value;
+    market.long_payout_bps = long_payout_bps as u16;
+    // `outcome` records which side came out ahead, for reputation.
+    let outcome = if long_payout_bps >= (BPS_DENOMINATOR / 2) as u128 {
+        MarketOutcome::Yes as u8
+    } else {
+        MarketOutcome::No as u8
+    };
+    apply_resolution(market, outcome)
+}
+
+/// Markets created with a signal oracle can only be resolved by that oracle.
+pub(crate) fn require_no_signal_oracle(market: &Market) -> Result<()> {
+    require!(
+        market.signal_oracle == Pubkey::default(),
+        SuperfanError::OracleResolutionRequired
+    );
+    Ok(())
+}
+
+pub(crate) fn resolve_from_signal(market: &mut Account<Market>, measured_bps: i64) -> Result<()> {
+    require!(
+        market.outcome_count == 2 && !market.is_scalar(),
+        SuperfanError::InvalidOutcome
+    );
+    let outcome = if measured_bps >= market.conviction_threshold_bps as i64 {
+        MarketOutcome::Yes as u8
+    } else {
+        MarketOutcome::No as u8
+    };
+    market.measured_bps = measured_bps;
+    apply_resolution(market, outcome)
+}
+
+/// Unlike a normal resolution this is allowed at any point before the market
+/// resolves, including mid-trading. The market ends up `Cancelled`, so every
+/// stake is refunded in full and the pool's reservation for it is released.
+fn void_market(market: &mut Account<Market>, pool: Option<&mut LiquidityPool>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let pool = pool.ok_or(SuperfanError::LiquidityPoolRequired)?;
+    require!(
+        market.status == MarketStatus::Pending as u8 || market.status == MarketStatus::Locked as u8,
+        SuperfanError::InvalidStatus
+    );
+    market.status = MarketStatus::Cancelled as u8;
+    market.outcome = MarketOutcome::Invalid as u8;
+    market.resolved_at = now;
+    sync_exposure(pool, market)?;
+
+    emit!(MarketResolved {
+        market: market.key(),
+        sponsor: market.sponsor,
+        outcome: market.outcome,
+        resolved_at: now,
+    });
+    Ok(())
+}
+
+fn apply_resolution(market: &mut Account<Market>, outcome: u8) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        outcome >= 1 && outcome <= market.outcome_count,
+        SuperfanError::InvalidOutcome
+    );
 
     require!(
         market.status != MarketStatus::Cancelled as u8,
@@ -99,12 +298,12 @@ pub fn resolve_market(ctx: Context<UpdateMarketStatus>, outcome_yes: bool) -> Re
 
     market.status = MarketStatus::Resolved as u8;
     market.resolved_at = now;
-    market.outcome = if outcome_yes { 1 } else { 2 };
+    market.outcome = outcome;
 
     emit!(MarketResolved {
         market: market.key(),
         sponsor: market.sponsor,
-        outcome_yes,
+        outcome,
         resolved_at: now,
     });
 
@@ -5,3 +5,4 @@ pub mod payouts;
 pub mod positions;
 pub mod reputation;
 pub mod signal;
+pub mod tokens;
@@ -1,9 +1,263 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
 
 use crate::contexts::*;
+use crate::errors::SuperfanError;
+use crate::events::PositionSettled;
+use crate::handlers::liquidity::{sync_exposure, transfer_from_vault};
+use crate::handlers::reputation::{
+    accrue_reputation, load_scout_registry, store_scout_registry,
+};
+use crate::state::{
+    bps_of, LiquidityPool, Market, MarketKind, MarketStatus, Position, ScoutRegistry,
+    SCOUT_TIER_THRESHOLDS,
+};
 
-pub fn distribute_payouts(_ctx: Context<DistributePayouts>) -> Result<()> {
-    // Placeholder: wire to token transfers + fee flows.
-    // Expected checks: market resolved, locked funds available, and iterate positions.
+/// Settles a batch of positions on a resolved market, or refunds them in full
+/// on a cancelled or voided one. Remaining accounts are
+/// `(position, user_token_account, scout_registry)` triples; already-settled
+/// positions are skipped so the crank can be re-run until every position is paid.
+/// The registry slot must be the holder's `scout` PDA; if they never created
+/// one they are paid without a reputation bonus, and refunds never read it.
+pub fn distribute_payouts<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DistributePayouts<'info>>,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let refunding = market.status == MarketStatus::Cancelled as u8;
+    require!(
+        refunding || market.status == MarketStatus::Resolved as u8,
+        SuperfanError::InvalidStatus
+    );
+    require!(
+        ctx.remaining_accounts.len() % 3 == 0,
+        SuperfanError::InvalidPayoutAccounts
+    );
+
+    for accounts in ctx.remaining_accounts.chunks(3) {
+        let mut position = Account::<Position>::try_from(&accounts[0])?;
+        require!(accounts[0].is_writable, SuperfanError::InvalidPayoutAccounts);
+        require!(
+            position.market == market.key(),
+            SuperfanError::InvalidPayoutAccounts
+        );
+        if position.settled {
+            continue;
+        }
+
+        let user_token_account = Account::<TokenAccount>::try_from(&accounts[1])?;
+        require!(
+            user_token_account.owner == position.user
+                && user_token_account.mint == ctx.accounts.config.usdc_mint,
+            SuperfanError::InvalidPayoutAccounts
+        );
+        let (registry_key, _) =
+            Pubkey::find_program_address(&[b"scout", position.user.as_ref()], &crate::ID);
+        require!(
+            accounts[2].key() == registry_key,
+            SuperfanError::InvalidPayoutAccounts
+        );
+        let mut scout_registry = if refunding || accounts[2].owner != &crate::ID {
+            None
+        } else {
+            require!(accounts[2].is_writable, SuperfanError::InvalidPayoutAccounts);
+            Some(Account::<ScoutRegistry>::try_from(&accounts[2])?)
+        };
+
+        let payout = if refunding {
+            sync_exposure(&mut ctx.accounts.liquidity_pool, market)?;
+            position.amount
+        } else {
+            settle_position(
+                &mut ctx.accounts.liquidity_pool,
+                market,
+                &position,
+                scout_registry.as_deref(),
+            )?
+        };
+        if payout > 0 {
+            transfer_from_vault(
+                &ctx.accounts.token_program,
+                &ctx.accounts.liquidity_pool,
+                &ctx.accounts.liquidity_vault,
+                accounts[1].clone(),
+                payout,
+            )?;
+        }
+        if let Some(registry) = scout_registry.as_mut() {
+            accrue_reputation(registry, &ctx.accounts.config, market, &position)?;
+            registry.exit(&crate::ID)?;
+        }
+
+        position.settled = true;
+        position.exit(&crate::ID)?;
+        emit!(PositionSettled {
+            market: market.key(),
+            user: position.user,
+            payout,
+        });
+    }
+    Ok(())
+}
+
+/// Redeems the caller's own position on a resolved market and closes it for
+/// rent. Positions already paid by the crank are simply closed.
+pub fn claim_winnings(ctx: Context<ClaimWinnings>) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    require!(
+        market.status == MarketStatus::Resolved as u8,
+        SuperfanError::InvalidStatus
+    );
+
+    let position = &ctx.accounts.position;
+    if position.settled {
+        return Ok(());
+    }
+    let mut scout_registry = load_scout_registry(&ctx.accounts.scout_registry)?;
+    let payout = settle_position(
+        &mut ctx.accounts.liquidity_pool,
+        market,
+        position,
+        scout_registry.as_ref(),
+    )?;
+    if payout > 0 {
+        transfer_from_vault(
+            &ctx.accounts.token_program,
+            &ctx.accounts.liquidity_pool,
+            &ctx.accounts.liquidity_vault,
+            ctx.accounts.user_token_account.to_account_info(),
+            payout,
+        )?;
+    }
+    if let Some(registry) = scout_registry.as_mut() {
+        accrue_reputation(registry, &ctx.accounts.config, market, position)?;
+        store_scout_registry(&ctx.accounts.scout_registry, registry)?;
+    }
+
+    emit!(PositionSettled {
+        market: market.key(),
+        user: position.user,
+        payout,
+    });
+    Ok(())
+}
+
+/// Returns what the vault owes the holder: stake plus winnings (and any
+/// reputation bonus) if their side won, nothing otherwise, or on a scalar
+/// market their side's linear share of that face value. The first settlement
+/// on a market moves every stake into pool capacity, after which the pool
+/// covers winners in full and only their outstanding payouts stay locked.
+pub(crate) fn settle_position(
+    pool: &mut LiquidityPool,
+    market: &mut Market,
+    position: &Position,
+    registry: Option<&ScoutRegistry>,
+) -> Result<u64> {
+    require!(!position.settled, SuperfanError::PositionAlreadySettled);
+    if market.market_kind == MarketKind::Parimutuel as u8 {
+        return settle_parimutuel(pool, market, position, registry);
+    }
+    release_stakes(pool, market)?;
+
+    let face = position.amount + position.winnings;
+    let owed = bps_of(face, market.payout_bps(position.side));
+    let i = Market::outcome_index(position.side);
+    market.outcome_payouts[i] = market.outcome_payouts[i].saturating_sub(face);
+    let payout = if owed > 0 {
+        // Reputation bonuses are paid on the profit above the stake.
+        let bonus = reputation_bonus(pool, registry, owed.saturating_sub(position.amount));
+        pool.reputation_bonus_budget = pool
+            .reputation_bonus_budget
+            .checked_sub(bonus)
+            .ok_or(SuperfanError::InsufficientLiquidity)?;
+        let payout = owed.checked_add(bonus).ok_or(SuperfanError::MathOverflow)?;
+        pool.capacity = pool
+            .capacity
+            .checked_sub(payout)
+            .ok_or(SuperfanError::InsufficientLiquidity)?;
+        payout
+    } else {
+        0
+    };
+    sync_exposure(pool, market)?;
+    Ok(payout)
+}
+
+/// Moves every stake on a resolved sponsor-backed market into pool capacity,
+/// once, so winners can be paid in full from the pool.
+pub(crate) fn release_stakes(pool: &mut LiquidityPool, market: &mut Market) -> Result<()> {
+    if !market.stakes_released {
+        pool.capacity = pool
+            .capacity
+            .checked_add(market.total_staked())
+            .ok_or(SuperfanError::MathOverflow)?;
+        market.stakes_released = true;
+    }
     Ok(())
 }
+
+/// Parimutuel winners get their stake back plus a pro-rata cut of the losing
+/// outcomes after `fee_bps`; the pool only adds early-supporter and reputation
+/// bonuses. If nobody backed the winning side every stake is refunded.
+fn settle_parimutuel(
+    pool: &mut LiquidityPool,
+    market: &mut Market,
+    position: &Position,
+    registry: Option<&ScoutRegistry>,
+) -> Result<u64> {
+    let w = Market::outcome_index(market.outcome);
+    let winning_total = market.outcome_totals[w];
+    let losing_total = market.total_staked() - winning_total;
+    if winning_total == 0 {
+        return Ok(position.amount);
+    }
+    if !market.stakes_released {
+        // Fees are the only part of the pot that goes to the sponsor; the
+        // rest is what the winning side's shares now pay out.
+        let fee = bps_of(losing_total, market.fee_bps);
+        pool.capacity = pool
+            .capacity
+            .checked_add(fee)
+            .ok_or(SuperfanError::MathOverflow)?;
+        market.outcome_payouts[w] = market.total_staked() - fee;
+        market.stakes_released = true;
+    }
+    if position.side != market.outcome {
+        return Ok(0);
+    }
+
+    // Each winner takes their share of what is left of the pot, so rounding
+    // dust rolls forward and the last winner to settle collects it.
+    let owed = (market.outcome_payouts[w] as u128 * position.shares as u128
+        / market.outcome_shares[w] as u128) as u64;
+    market.outcome_payouts[w] -= owed;
+    market.outcome_shares[w] -= position.shares;
+    let winnings = owed.saturating_sub(position.amount);
+    // Only the opening stake's cut of the pot earns the early-supporter bonus,
+    // not later top-ups. Bonuses are not reserved up front, so they are paid
+    // only as far as the pool's free capital allows.
+    let early_owed =
+        (owed as u128 * position.early_shares as u128 / position.shares as u128) as u64;
+    let early_winnings = early_owed.saturating_sub(position.early_shares);
+    let early_bonus = bps_of(early_winnings, pool.early_bonus_bps).min(pool.available());
+    let reputation_bonus = reputation_bonus(pool, registry, winnings);
+    pool.reputation_bonus_budget = pool
+        .reputation_bonus_budget
+        .checked_sub(reputation_bonus)
+        .ok_or(SuperfanError::InsufficientLiquidity)?;
+    pool.capacity = pool
+        .capacity
+        .checked_sub(early_bonus + reputation_bonus)
+        .ok_or(SuperfanError::InsufficientLiquidity)?;
+    Ok(owed + early_bonus + reputation_bonus)
+}
+
+/// Tiered share of `reputation_bonus_max_bps` on the winnings, capped by what
+/// is left of the sponsor's bonus budget. Holders without a registry get none.
+fn reputation_bonus(pool: &LiquidityPool, registry: Option<&ScoutRegistry>, winnings: u64) -> u64 {
+    let Some(registry) = registry else {
+        return 0;
+    };
+    let max_tier = SCOUT_TIER_THRESHOLDS.len() as u64;
+    let bonus_bps = pool.reputation_bonus_max_bps as u64 * registry.tier() as u64 / max_tier;
+    bps_of(winnings, bonus_bps as u16).min(pool.reputation_bonus_budget)
+}
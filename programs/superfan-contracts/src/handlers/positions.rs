@@ -3,26 +3,56 @@ use anchor_spl::token::{self, Transfer};
 
 use crate::contexts::*;
 use crate::errors::SuperfanError;
-use crate::events::PositionOpened;
+use crate::events::{
+    PositionClosed, PositionDecreased, PositionIncreased, PositionOpened, PositionTransferred,
+};
+use crate::handlers::liquidity::{sync_exposure, transfer_from_vault};
+use crate::handlers::payouts::settle_position;
+use crate::handlers::reputation::{
+    accrue_reputation, load_scout_registry, roll_season, store_scout_registry,
+};
+use crate::lmsr;
+use crate::state::{
+    bps_of, LiquidityPool, Market, MarketKind, MarketStatus, ScoutRegistry,
+    SuperfanConfig, BPS_DENOMINATOR,
+};
 
-pub fn open_position(ctx: Context<OpenPosition>, amount: u64) -> Result<()> {
+/// `min_shares_out` and `expires_at` bound the terms the fan signed for: the
+/// trade fails rather than fill at a worse price or after the quote went stale.
+/// Parimutuel stakes always receive one share per unit.
+pub fn open_position(
+    ctx: Context<OpenPosition>,
+    amount: u64,
+    outcome: u8,
+    min_shares_out: u64,
+    expires_at: i64,
+) -> Result<()> {
     require!(amount > 0, SuperfanError::InvalidAmount);
     let now = Clock::get()?.unix_timestamp;
-    let market = &ctx.accounts.market;
+    require!(now <= expires_at, SuperfanError::OrderExpired);
+    let market = &mut ctx.accounts.market;
+    check_entry(
+        market,
+        &ctx.accounts.config,
+        ctx.accounts.scout_registry.as_deref_mut(),
+        now,
+    )?;
     require!(
-        market.status == crate::state::MarketStatus::Pending as u8,
-        SuperfanError::InvalidStatus
+        outcome >= 1 && outcome <= market.outcome_count,
+        SuperfanError::InvalidOutcome
     );
-    require!(now >= market.trading_starts_at, SuperfanError::TradingStillOpen);
-    require!(now <= market.trading_ends_at, SuperfanError::TradingAlreadyStarted);
 
+    // The first `first_n_limit` positions of at least `early_min_stake` on a
+    // market are early supporters and earn the pool's boosted multiplier on
+    // their winnings; smaller stakes don't use up a slot.
+    let entry_prob_bps = quote_bps(market, outcome)?;
     let pool = &mut ctx.accounts.liquidity_pool;
-    let new_locked = pool
-        .locked
-        .checked_add(amount)
-        .ok_or(SuperfanError::MathOverflow)?;
-    require!(new_locked <= pool.capacity, SuperfanError::InsufficientLiquidity);
-    pool.locked = new_locked;
+    let early_supporter = amount >= pool.early_min_stake
+        && market.early_supporter_count < pool.first_n_limit as u32;
+    let (shares, winnings) = buy(market, pool, outcome, amount, early_supporter, min_shares_out)?;
+    if early_supporter {
+        market.early_supporter_count += 1;
+    }
 
     // Transfer stake into the pool vault
     let cpi_accounts = Transfer {
@@ -36,39 +66,415 @@ pub fn open_position(ctx: Context<OpenPosition>, amount: u64) -> Result<()> {
     let position = &mut ctx.accounts.position;
     position.user = ctx.accounts.user.key();
     position.market = ctx.accounts.market.key();
+    position.side = outcome;
     position.amount = amount;
-    position.created_at = Clock::get()?.unix_timestamp;
+    position.winnings = winnings;
+    position.early_supporter = early_supporter;
+    position.early_shares = if early_supporter { shares } else { 0 };
+    position.entry_prob_bps = entry_prob_bps;
+    position.shares = shares;
+    position.created_at = now;
+    position.settled = false;
     position.bump = ctx.bumps.position;
     emit!(PositionOpened {
+        market: position.market,
+        user: position.user,
+        outcome,
+        amount,
+        shares,
+        implied_prob_bps: ctx.accounts.market.implied_probs_bps[Market::outcome_index(outcome)],
+        early_supporter,
+    });
+    Ok(())
+}
+
+/// Adds stake to an existing position on the same side, under the same entry
+/// rules and slippage guards as `open_position`. Entry price and time become
+/// stake-weighted averages so late top-ups don't inherit an early entry.
+pub fn increase_position(
+    ctx: Context<AdjustPosition>,
+    amount: u64,
+    min_shares_out: u64,
+    expires_at: i64,
+) -> Result<()> {
+    require!(amount > 0, SuperfanError::InvalidAmount);
+    let now = Clock::get()?.unix_timestamp;
+    require!(now <= expires_at, SuperfanError::OrderExpired);
+    let market = &mut ctx.accounts.market;
+    check_entry(
+        market,
+        &ctx.accounts.config,
+        ctx.accounts.scout_registry.as_deref_mut(),
+        now,
+    )?;
+
+    let position = &mut ctx.accounts.position;
+    let entry_prob_bps = quote_bps(market, position.side)?;
+    // The early-supporter bonus only covers the opening stake; top-ups are
+    // priced like any other late entry.
+    let (shares, winnings) = buy(
+        market,
+        &mut ctx.accounts.liquidity_pool,
+        position.side,
+        amount,
+        false,
+        min_shares_out,
+    )?;
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.user_token_account.to_account_info(),
+        to: ctx.accounts.liquidity_vault.to_account_info(),
+        authority: ctx.accounts.user.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, amount)?;
+
+    position.entry_prob_bps = stake_weighted(
+        position.entry_prob_bps as i128,
+        position.amount,
+        entry_prob_bps as i128,
+        amount,
+    ) as u16;
+    position.created_at =
+        stake_weighted(position.created_at as i128, position.amount, now as i128, amount) as i64;
+    position.amount = position
+        .amount
+        .checked_add(amount)
+        .ok_or(SuperfanError::MathOverflow)?;
+    position.shares = position
+        .shares
+        .checked_add(shares)
+        .ok_or(SuperfanError::MathOverflow)?;
+    position.winnings = position
+        .winnings
+        .checked_add(winnings)
+        .ok_or(SuperfanError::MathOverflow)?;
+    emit!(PositionIncreased {
+        market: position.market,
+        user: position.user,
+        amount,
+        shares,
+        implied_prob_bps: market.implied_probs_bps[Market::outcome_index(position.side)],
+    });
+    Ok(())
+}
+
+/// Sells back a proportional slice of a position while trading is open, on the
+/// same terms as an early exit. Use `close_position` to exit in full.
+pub fn decrease_position(
+    ctx: Context<AdjustPosition>,
+    amount: u64,
+    min_payout: u64,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let market = &mut ctx.accounts.market;
+    require!(
+        market.status == MarketStatus::Pending as u8,
+        SuperfanError::InvalidStatus
+    );
+    require!(now <= market.trading_ends_at, SuperfanError::TradingClosed);
+    let position = &mut ctx.accounts.position;
+    require!(
+        amount > 0 && amount < position.amount,
+        SuperfanError::InvalidAmount
+    );
+
+    let shares = pro_rata(position.shares, amount, position.amount);
+    let winnings = pro_rata(position.winnings, amount, position.amount);
+    let early_shares = pro_rata(position.early_shares, amount, position.amount);
+    let payout = sell(
+        market,
+        &mut ctx.accounts.liquidity_pool,
+        position.side,
+        amount,
+        shares,
+        winnings,
+    )?;
+    require!(payout >= min_payout, SuperfanError::SlippageExceeded);
+    position.amount -= amount;
+    position.shares -= shares;
+    position.winnings -= winnings;
+    position.early_shares -= early_shares;
+
+    if payout > 0 {
+        transfer_from_vault(
+            &ctx.accounts.token_program,
+            &ctx.accounts.liquidity_pool,
+            &ctx.accounts.liquidity_vault,
+            ctx.accounts.user_token_account.to_account_info(),
+            payout,
+        )?;
+    }
+
+    emit!(PositionDecreased {
         market: position.market,
         user: position.user,
         amount,
+        payout,
+    });
+    Ok(())
+}
+
+/// Moves a position to another wallet while trading is open. The position is
+/// re-created under the recipient's seeds and the sender's account is closed;
+/// the recipient must meet the same entry rules as a new buyer.
+pub fn transfer_position(ctx: Context<TransferPosition>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let recipient = ctx.accounts.recipient.key();
+    require!(recipient != ctx.accounts.user.key(), SuperfanError::Unauthorized);
+    check_entry(
+        &ctx.accounts.market,
+        &ctx.accounts.config,
+        ctx.accounts.recipient_scout_registry.as_deref_mut(),
+        now,
+    )?;
+
+    let position = &ctx.accounts.position;
+    let recipient_position = &mut ctx.accounts.recipient_position;
+    recipient_position.user = recipient;
+    recipient_position.market = position.market;
+    recipient_position.side = position.side;
+    recipient_position.amount = position.amount;
+    recipient_position.winnings = position.winnings;
+    recipient_position.early_supporter = position.early_supporter;
+    recipient_position.early_shares = position.early_shares;
+    recipient_position.entry_prob_bps = position.entry_prob_bps;
+    recipient_position.shares = position.shares;
+    recipient_position.created_at = position.created_at;
+    recipient_position.settled = false;
+    recipient_position.bump = ctx.bumps.recipient_position;
+    emit!(PositionTransferred {
+        market: position.market,
+        from: position.user,
+        to: recipient,
+        amount: position.amount,
     });
     Ok(())
 }
 
 pub fn close_position(ctx: Context<ClosePosition>) -> Result<()> {
-    // Placeholder: redemption/payout logic. For now, refund staked amount.
+    let now = Clock::get()?.unix_timestamp;
     let position = &ctx.accounts.position;
+    if position.settled {
+        // Already paid or refunded by the crank; just reclaim the rent.
+        return Ok(());
+    }
     let amount = position.amount;
+    let market = &mut ctx.accounts.market;
     let pool = &mut ctx.accounts.liquidity_pool;
-    pool.locked = pool.locked.saturating_sub(amount);
-
-    // transfer back to user from vault, using pool as authority
-    let seeds: &[&[u8]] = &[
-        b"liquidity_pool",
-        ctx.accounts.liquidity_pool.sponsor.as_ref(),
-        &[ctx.accounts.liquidity_pool.bump],
-    ];
-    let signer = &[seeds];
-    let cpi_accounts = Transfer {
-        from: ctx.accounts.liquidity_vault.to_account_info(),
-        to: ctx.accounts.user_token_account.to_account_info(),
-        authority: ctx.accounts.liquidity_pool.to_account_info(),
+
+    let payout = if market.status == MarketStatus::Pending as u8 {
+        // Early exit while trading is open.
+        require!(now <= market.trading_ends_at, SuperfanError::TradingClosed);
+        sell(market, pool, position.side, amount, position.shares, position.winnings)?
+    } else if market.status == MarketStatus::Resolved as u8 {
+        let mut registry = load_scout_registry(&ctx.accounts.scout_registry)?;
+        let payout = settle_position(pool, market, position, registry.as_ref())?;
+        if let Some(registry) = registry.as_mut() {
+            accrue_reputation(registry, &ctx.accounts.config, market, position)?;
+            store_scout_registry(&ctx.accounts.scout_registry, registry)?;
+        }
+        payout
+    } else if market.status == MarketStatus::Cancelled as u8 {
+        sync_exposure(pool, market)?;
+        amount
+    } else {
+        // Locked: stakes are committed until the market resolves.
+        return err!(SuperfanError::InvalidStatus);
     };
-    let cpi_ctx =
-        CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer);
-    token::transfer(cpi_ctx, amount)?;
 
+    if payout > 0 {
+        // transfer back to user from vault, using pool as authority
+        transfer_from_vault(
+            &ctx.accounts.token_program,
+            &ctx.accounts.liquidity_pool,
+            &ctx.accounts.liquidity_vault,
+            ctx.accounts.user_token_account.to_account_info(),
+            payout,
+        )?;
+    }
+
+    emit!(PositionClosed {
+        market: market.key(),
+        user: position.user,
+        payout,
+    });
+    Ok(())
+}
+
+/// Status, timing and reputation rules for adding stake to a market.
+pub(crate) fn check_entry(
+    market: &Market,
+    config: &SuperfanConfig,
+    registry: Option<&mut ScoutRegistry>,
+    now: i64,
+) -> Result<()> {
+    require!(
+        market.status == MarketStatus::Pending as u8,
+        SuperfanError::InvalidStatus
+    );
+    require!(now <= market.trading_ends_at, SuperfanError::TradingClosed);
+
+    // Before general trading only scouts above the early-access bar may enter.
+    let early_access = now < market.trading_starts_at;
+    if early_access {
+        require!(
+            market.early_access_starts_at.is_some_and(|starts_at| now >= starts_at),
+            SuperfanError::TradingNotStarted
+        );
+    }
+    if early_access || market.min_reputation.is_some() {
+        let registry = registry.ok_or(SuperfanError::ScoutRegistryRequired)?;
+        roll_season(registry, config);
+        require!(
+            market.min_reputation.is_none_or(|min| registry.score >= min),
+            SuperfanError::ReputationTooLow
+        );
+        require!(
+            !early_access || registry.score >= market.early_access_min_reputation,
+            SuperfanError::ReputationTooLow
+        );
+    }
+    Ok(())
+}
+
+/// Prices `amount` of new stake on `side`, books it and reserves the pool
+/// capital it puts at risk. Returns `(shares, winnings)`.
+pub(crate) fn buy(
+    market: &mut Market,
+    pool: &mut LiquidityPool,
+    side: u8,
+    amount: u64,
+    early_supporter: bool,
+    min_shares_out: u64,
+) -> Result<(u64, u64)> {
+    let (shares, winnings) = if market.market_kind == MarketKind::Parimutuel as u8 {
+        // One share per unit staked; winnings depend on the final pot.
+        (amount, 0)
+    } else {
+        // Shares are bought from the market's LMSR and each pays one base unit
+        // if its side wins.
+        let q = &market.outcome_shares[..market.outcome_count as usize];
+        let shares = lmsr::buy_shares(market.lmsr_b, q, Market::outcome_index(side), amount)?;
+        let base_winnings = shares.saturating_sub(amount);
+        let winnings = if early_supporter {
+            base_winnings
+                .checked_add(bps_of(base_winnings, pool.early_bonus_bps))
+                .ok_or(SuperfanError::MathOverflow)?
+        } else {
+            base_winnings
+        };
+        (shares, winnings)
+    };
+    require!(shares >= min_shares_out, SuperfanError::SlippageExceeded);
+    let payout = amount
+        .checked_add(winnings)
+        .ok_or(SuperfanError::MathOverflow)?;
+    book_position(market, side, amount, shares, payout)?;
+    sync_exposure(pool, market)?;
+    require!(
+        market.pool_exposure <= market.max_pool_exposure,
+        SuperfanError::MarketExposureExceeded
+    );
+    Ok((shares, winnings))
+}
+
+/// Takes `amount` of stake (with its `shares` and `winnings`) off the book
+/// before resolution and returns the refund: LMSR sale proceeds, or the stake
+/// itself on a parimutuel market, less the early-exit penalty.
+fn sell(
+    market: &mut Market,
+    pool: &mut LiquidityPool,
+    side: u8,
+    amount: u64,
+    shares: u64,
+    winnings: u64,
+) -> Result<u64> {
+    let proceeds = if market.market_kind == MarketKind::Parimutuel as u8 {
+        amount
+    } else {
+        let q = &market.outcome_shares[..market.outcome_count as usize];
+        lmsr::sell_proceeds(market.lmsr_b, q, Market::outcome_index(side), shares)?
+    };
+    let refund = proceeds - bps_of(proceeds, market.early_exit_penalty_bps);
+    unbook_position(market, side, amount, shares, amount + winnings)?;
+    // The stake leaves the book, so the pool gains or covers the difference.
+    pool.capacity = pool
+        .capacity
+        .checked_add(amount)
+        .ok_or(SuperfanError::MathOverflow)?
+        .checked_sub(refund)
+        .ok_or(SuperfanError::InsufficientLiquidity)?;
+    sync_exposure(pool, market)?;
+    Ok(refund)
+}
+
+/// Current price of outcome `side` in bps: the LMSR quote, or for parimutuel
+/// markets the outcome's share of total stake (an empty pot is an even split).
+fn quote_bps(market: &Market, side: u8) -> Result<u16> {
+    let n = market.outcome_count as usize;
+    let i = Market::outcome_index(side);
+    if market.market_kind == MarketKind::Parimutuel as u8 {
+        let total = market.total_staked();
+        if total == 0 {
+            return Ok((BPS_DENOMINATOR / n as u64) as u16);
+        }
+        let share = market.outcome_totals[i] as u128 * BPS_DENOMINATOR as u128 / total as u128;
+        return Ok(share as u16);
+    }
+    lmsr::price_bps(market.lmsr_b, &market.outcome_shares[..n], i)
+}
+
+/// Adds a position's stake, shares and potential payout to its outcome and
+/// re-quotes the implied probabilities.
+fn book_position(
+    market: &mut Market,
+    side: u8,
+    amount: u64,
+    shares: u64,
+    payout: u64,
+) -> Result<()> {
+    let i = Market::outcome_index(side);
+    market.outcome_totals[i] = market.outcome_totals[i]
+        .checked_add(amount)
+        .ok_or(SuperfanError::MathOverflow)?;
+    market.outcome_shares[i] = market.outcome_shares[i]
+        .checked_add(shares)
+        .ok_or(SuperfanError::MathOverflow)?;
+    market.outcome_payouts[i] = market.outcome_payouts[i]
+        .checked_add(payout)
+        .ok_or(SuperfanError::MathOverflow)?;
+    refresh_implied_probs(market)
+}
+
+/// Reverses `book_position` for stake leaving before resolution.
+fn unbook_position(
+    market: &mut Market,
+    side: u8,
+    amount: u64,
+    shares: u64,
+    payout: u64,
+) -> Result<()> {
+    let i = Market::outcome_index(side);
+    market.outcome_totals[i] = market.outcome_totals[i].saturating_sub(amount);
+    market.outcome_shares[i] = market.outcome_shares[i].saturating_sub(shares);
+    market.outcome_payouts[i] = market.outcome_payouts[i].saturating_sub(payout);
+    refresh_implied_probs(market)
+}
+
+fn refresh_implied_probs(market: &mut Market) -> Result<()> {
+    for outcome in 1..=market.outcome_count {
+        market.implied_probs_bps[Market::outcome_index(outcome)] = quote_bps(market, outcome)?;
+    }
     Ok(())
 }
+
+/// `value * part / whole`, rounded down.
+fn pro_rata(value: u64, part: u64, whole: u64) -> u64 {
+    (value as u128 * part as u128 / whole as u128) as u64
+}
+
+/// Average of `a` and `b` weighted by their stakes.
+fn stake_weighted(a: i128, a_stake: u64, b: i128, b_stake: u64) -> i128 {
+    (a * a_stake as i128 + b * b_stake as i128) / (a_stake as i128 + b_stake as i128)
+}
@@ -2,7 +2,31 @@ use anchor_lang::prelude::*;
 
 use crate::contexts::*;
 use crate::errors::SuperfanError;
-use crate::events::ReputationUpdated;
+use crate::events::{ReputationUpdated, ScoutSeasonClosed};
+use crate::state::{
+    bps_of, Market, Position, ScoutRegistry, ScoutStats, SuperfanConfig, BPS_DENOMINATOR,
+    REPUTATION_STAKE_UNIT,
+};
+
+/// Decay stops compounding after this many skipped seasons; by then any
+/// realistic score has long since reached zero.
+const MAX_DECAY_SEASONS: u32 = 64;
+
+pub fn init_scout(ctx: Context<InitScout>) -> Result<()> {
+    let registry = &mut ctx.accounts.scout_registry;
+    registry.user = ctx.accounts.user.key();
+    registry.score = 0;
+    registry.markets_participated = 0;
+    registry.wins = 0;
+    registry.losses = 0;
+    registry.cumulative_stake = 0;
+    registry.brier_sum_bps = 0;
+    registry.season = ctx.accounts.config.current_season;
+    registry.season_score = 0;
+    registry.last_season_score = 0;
+    registry.bump = ctx.bumps.scout_registry;
+    Ok(())
+}
 
 pub fn update_reputation(ctx: Context<UpdateReputation>, delta: i64) -> Result<()> {
     let config = &ctx.accounts.config;
@@ -10,15 +34,121 @@ pub fn update_reputation(ctx: Context<UpdateReputation>, delta: i64) -> Result<(
         ctx.accounts.admin.key() == config.admin,
         SuperfanError::Unauthorized
     );
-    let registry = &mut ctx.accounts.scout_registry;
-    registry.user = ctx.accounts.user.key();
+    add_score(&mut ctx.accounts.scout_registry, config, delta)
+}
+
+pub fn roll_scout_season(ctx: Context<RollScoutSeason>) -> Result<()> {
+    roll_season(&mut ctx.accounts.scout_registry, &ctx.accounts.config);
+    Ok(())
+}
+
+pub fn scout_stats(ctx: Context<ReadScout>) -> Result<ScoutStats> {
+    Ok(ctx.accounts.scout_registry.stats())
+}
+
+/// Reads the registry at a holder's `scout` PDA, or `None` if they never
+/// created one; those holders settle without reputation.
+pub(crate) fn load_scout_registry(info: &AccountInfo) -> Result<Option<ScoutRegistry>> {
+    if info.owner != &crate::ID {
+        return Ok(None);
+    }
+    let data = info.try_borrow_data()?;
+    Ok(Some(ScoutRegistry::try_deserialize(&mut &data[..])?))
+}
+
+/// Writes back a registry read with `load_scout_registry`.
+pub(crate) fn store_scout_registry(info: &AccountInfo, registry: &ScoutRegistry) -> Result<()> {
+    let mut data = info.try_borrow_mut_data()?;
+    registry.try_serialize(&mut &mut data[..])
+}
+
+/// Applies the reputation earned by settling `position` on its resolved market.
+pub(crate) fn accrue_reputation(
+    registry: &mut ScoutRegistry,
+    config: &SuperfanConfig,
+    market: &Market,
+    position: &Position,
+) -> Result<()> {
+    let won = position.side == market.outcome;
+    registry.markets_participated = registry.markets_participated.saturating_add(1);
+    if won {
+        registry.wins = registry.wins.saturating_add(1);
+    } else {
+        registry.losses = registry.losses.saturating_add(1);
+    }
+    registry.cumulative_stake = registry.cumulative_stake.saturating_add(position.amount);
+    registry.brier_sum_bps = registry
+        .brier_sum_bps
+        .saturating_add(brier_bps(position.entry_prob_bps, won));
+    add_score(registry, config, settlement_delta(market, position))
+}
+
+fn add_score(registry: &mut ScoutRegistry, config: &SuperfanConfig, delta: i64) -> Result<()> {
+    roll_season(registry, config);
     registry.score = registry
         .score
         .checked_add(delta)
         .ok_or(SuperfanError::MathOverflow)?;
+    registry.season_score = registry
+        .season_score
+        .checked_add(delta)
+        .ok_or(SuperfanError::MathOverflow)?;
     emit!(ReputationUpdated {
         user: registry.user,
         new_score: registry.score,
+        season: registry.season,
+        season_score: registry.season_score,
     });
     Ok(())
 }
+
+/// Brings a registry up to the config's current season: snapshots the season
+/// it was last active in, then decays the lifetime score once per elapsed season.
+pub(crate) fn roll_season(registry: &mut ScoutRegistry, config: &SuperfanConfig) {
+    if registry.season >= config.current_season {
+        return;
+    }
+    emit!(ScoutSeasonClosed {
+        user: registry.user,
+        season: registry.season,
+        season_score: registry.season_score,
+    });
+    let elapsed = config.current_season - registry.season;
+    registry.last_season_score = if elapsed == 1 {
+        registry.season_score
+    } else {
+        0
+    };
+    let keep_bps = (BPS_DENOMINATOR - config.season_decay_bps as u64) as u16;
+    for _ in 0..elapsed.min(MAX_DECAY_SEASONS) {
+        if registry.score == 0 {
+            break;
+        }
+        let magnitude = bps_of(registry.score.unsigned_abs(), keep_bps) as i64;
+        registry.score = registry.score.signum() * magnitude;
+    }
+    registry.season_score = 0;
+    registry.season = config.current_season;
+}
+
+/// One point per `REPUTATION_STAKE_UNIT` staked. Correct calls are boosted by
+/// up to 2x for entering at `trading_starts_at`, decaying linearly to 1x at
+/// `trading_ends_at`; wrong calls lose the unboosted points.
+fn settlement_delta(market: &Market, position: &Position) -> i64 {
+    let points = (position.amount / REPUTATION_STAKE_UNIT) as i64;
+    if position.side != market.outcome {
+        return -points;
+    }
+    let window = (market.trading_ends_at - market.trading_starts_at).max(1) as i128;
+    let remaining = (market.trading_ends_at - position.created_at).clamp(0, window as i64) as i128;
+    let earliness_bps = remaining * BPS_DENOMINATOR as i128 / window;
+    (points as i128 * (BPS_DENOMINATOR as i128 + earliness_bps) / BPS_DENOMINATOR as i128) as i64
+}
+
+/// Brier score of the market's implied probability for the backed side at
+/// entry: `(outcome - p)^2`, in bps.
+fn brier_bps(entry_prob_bps: u16, won: bool) -> u64 {
+    let outcome_bps = if won { BPS_DENOMINATOR } else { 0 };
+    let error = outcome_bps.abs_diff(entry_prob_bps as u64);
+    error * error / BPS_DENOMINATOR
+}
@@ -1,21 +1,205 @@
 use anchor_lang::prelude::*;
+use solana_sha256_hasher::hashv;
 
 use crate::contexts::*;
-use crate::events::SignalCommitted;
+use crate::errors::SuperfanError;
+use crate::events::{SignalCommitted, SignalVerified};
+use crate::handlers::market::{require_no_signal_oracle, resolve_from_signal};
 
 pub fn submit_signal_commitment(
     ctx: Context<SubmitSignalCommitment>,
     commitment_root: [u8; 32],
+    resolution_metric: [u8; 32],
 ) -> Result<()> {
     let commit = &mut ctx.accounts.signal_commit;
     commit.market = ctx.accounts.market.key();
     commit.commitment_root = commitment_root;
+    commit.resolution_metric = resolution_metric;
     commit.recorded_at = Clock::get()?.unix_timestamp;
     commit.bump = ctx.bumps.signal_commit;
     emit!(SignalCommitted {
         market: commit.market,
         commitment_root,
+        resolution_metric,
         recorded_at: commit.recorded_at,
     });
     Ok(())
 }
+
+/// Proves a single datapoint (a fan's stream count, the artist's metric, ...)
+/// against the market's committed root.
+pub fn verify_signal(
+    ctx: Context<VerifySignal>,
+    subject: Pubkey,
+    metric: [u8; 32],
+    value: i64,
+    proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    let market = ctx.accounts.market.key();
+    verify_signal_leaf(
+        &ctx.accounts.signal_commit.commitment_root,
+        &market,
+        &subject,
+        &metric,
+        value,
+        &proof,
+    )?;
+    emit!(SignalVerified {
+        market,
+        subject,
+        metric,
+        value,
+    });
+    Ok(())
+}
+
+/// Permissionless resolution: anyone holding a proof of the artist's metric
+/// against the committed root can resolve against `conviction_threshold_bps`.
+/// Only the leaf tagged with the commitment's `resolution_metric` counts, so
+/// other datapoints for the artist in the same tree can't be used instead.
+pub fn resolve_market_with_proof(
+    ctx: Context<ProofResolveMarket>,
+    measured_bps: i64,
+    proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    require_no_signal_oracle(market)?;
+    let commit = &ctx.accounts.signal_commit;
+    verify_signal_leaf(
+        &commit.commitment_root,
+        &market.key(),
+        &market.artist_wallet,
+        &commit.resolution_metric,
+        measured_bps,
+        &proof,
+    )?;
+    emit!(SignalVerified {
+        market: market.key(),
+        subject: market.artist_wallet,
+        metric: commit.resolution_metric,
+        value: measured_bps,
+    });
+    resolve_from_signal(market, measured_bps)
+}
+
+/// Leaves are `sha256(0x00 || market || subject || metric || value_le)` and
+/// interior nodes `sha256(0x01 || min(a, b) || max(a, b))`, so proofs carry no
+/// left/right flags and a leaf can never be passed off as a node. `metric` is
+/// an off-chain tag naming what was measured and over which period.
+pub(crate) fn verify_signal_leaf(
+    root: &[u8; 32],
+    market: &Pubkey,
+    subject: &Pubkey,
+    metric: &[u8; 32],
+    value: i64,
+    proof: &[[u8; 32]],
+) -> Result<()> {
+    let mut node = signal_leaf(market, subject, metric, value);
+    for sibling in proof {
+        node = signal_node(&node, sibling);
+    }
+    require!(node == *root, SuperfanError::InvalidSignalProof);
+    Ok(())
+}
+
+fn signal_leaf(market: &Pubkey, subject: &Pubkey, metric: &[u8; 32], value: i64) -> [u8; 32] {
+    hashv(&[
+        &[0u8],
+        market.as_ref(),
+        subject.as_ref(),
+        metric,
+        &value.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+fn signal_node(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (a, b) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[&[1u8], a, b]).to_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STREAMS: [u8; 32] = [1; 32];
+    const FOLLOWERS: [u8; 32] = [2; 32];
+
+    /// Four leaves for one market: the artist's streams and followers and two
+    /// fans' stream counts. Returns the leaves and the root.
+    fn tree(market: &Pubkey, artist: &Pubkey, fans: &[Pubkey; 2]) -> ([[u8; 32]; 4], [u8; 32]) {
+        let leaves = [
+            signal_leaf(market, artist, &STREAMS, 7_500),
+            signal_leaf(market, artist, &FOLLOWERS, 2_000),
+            signal_leaf(market, &fans[0], &STREAMS, 120),
+            signal_leaf(market, &fans[1], &STREAMS, 45),
+        ];
+        let left = signal_node(&leaves[0], &leaves[1]);
+        let right = signal_node(&leaves[2], &leaves[3]);
+        (leaves, signal_node(&left, &right))
+    }
+
+    #[test]
+    fn verifies_every_leaf_in_the_tree() {
+        let (market, artist) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let fans = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let (leaves, root) = tree(&market, &artist, &fans);
+        let left = signal_node(&leaves[0], &leaves[1]);
+        let right = signal_node(&leaves[2], &leaves[3]);
+
+        assert!(verify_signal_leaf(&root, &market, &artist, &STREAMS, 7_500, &[leaves[1], right])
+            .is_ok());
+        assert!(
+            verify_signal_leaf(&root, &market, &artist, &FOLLOWERS, 2_000, &[leaves[0], right])
+                .is_ok()
+        );
+        assert!(verify_signal_leaf(&root, &market, &fans[0], &STREAMS, 120, &[leaves[3], left])
+            .is_ok());
+        assert!(verify_signal_leaf(&root, &market, &fans[1], &STREAMS, 45, &[leaves[2], left])
+            .is_ok());
+    }
+
+    #[test]
+    fn rejects_tampered_datapoints() {
+        let (market, artist) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let fans = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let (leaves, root) = tree(&market, &artist, &fans);
+        let right = signal_node(&leaves[2], &leaves[3]);
+        let proof = [leaves[1], right];
+
+        // Wrong value, subject, metric or market.
+        assert!(verify_signal_leaf(&root, &market, &artist, &STREAMS, 7_501, &proof).is_err());
+        assert!(verify_signal_leaf(&root, &market, &fans[0], &STREAMS, 7_500, &proof).is_err());
+        assert!(verify_signal_leaf(&root, &market, &artist, &FOLLOWERS, 7_500, &proof).is_err());
+        let other_market = Pubkey::new_unique();
+        assert!(
+            verify_signal_leaf(&root, &other_market, &artist, &STREAMS, 7_500, &proof).is_err()
+        );
+        // Truncated or padded proofs.
+        assert!(verify_signal_leaf(&root, &market, &artist, &STREAMS, 7_500, &proof[..1]).is_err());
+        assert!(verify_signal_leaf(
+            &root,
+            &market,
+            &artist,
+            &STREAMS,
+            7_500,
+            &[leaves[1], right, [0; 32]]
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn subtree_roots_only_verify_their_own_leaves() {
+        let (market, artist) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let fans = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let (leaves, root) = tree(&market, &artist, &fans);
+        let left = signal_node(&leaves[0], &leaves[1]);
+
+        assert!(verify_signal_leaf(&leaves[0], &market, &artist, &STREAMS, 7_500, &[]).is_ok());
+        assert!(verify_signal_leaf(&left, &market, &artist, &STREAMS, 7_500, &[leaves[1]]).is_ok());
+        let partial_proof = [leaves[1]];
+        assert!(
+            verify_signal_leaf(&root, &market, &artist, &STREAMS, 7_500, &partial_proof).is_err()
+        );
+    }
+}
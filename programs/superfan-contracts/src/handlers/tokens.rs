@@ -0,0 +1,198 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Burn, MintTo, Transfer};
+
+use crate::contexts::*;
+use crate::errors::SuperfanError;
+use crate::events::{OutcomeTokensBought, OutcomeTokensRedeemed};
+use crate::handlers::liquidity::{sync_exposure, transfer_from_vault};
+use crate::handlers::payouts::release_stakes;
+use crate::handlers::positions::{buy, check_entry};
+use crate::state::{bps_of, Market, MarketKind, MarketOutcome, MarketStatus};
+
+/// Creates the market's YES and NO outcome token mints, with the market PDA as
+/// mint authority. Only binary sponsor-backed markets can be tokenized, since
+/// every token redeems for exactly one base unit of USDC.
+pub fn create_outcome_mints(ctx: Context<CreateOutcomeMints>) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    require!(
+        market.status == MarketStatus::Pending as u8,
+        SuperfanError::InvalidStatus
+    );
+    require!(
+        market.market_kind == MarketKind::SponsorBacked as u8,
+        SuperfanError::InvalidMarketKind
+    );
+    require!(market.outcome_count == 2, SuperfanError::InvalidOutcome);
+    market.yes_mint = ctx.accounts.yes_mint.key();
+    market.no_mint = ctx.accounts.no_mint.key();
+    Ok(())
+}
+
+/// Buys LMSR shares like `open_position`, but mints them to the fan's wallet
+/// as transferable outcome tokens instead of recording a `Position`. Tokens
+/// carry no early-supporter bonus and accrue no reputation.
+pub fn buy_outcome_tokens(
+    ctx: Context<BuyOutcomeTokens>,
+    amount: u64,
+    side_yes: bool,
+    min_shares_out: u64,
+    expires_at: i64,
+) -> Result<()> {
+    require!(amount > 0, SuperfanError::InvalidAmount);
+    let now = Clock::get()?.unix_timestamp;
+    require!(now <= expires_at, SuperfanError::OrderExpired);
+    let market = &mut ctx.accounts.market;
+    let expected_mint = if side_yes {
+        market.yes_mint
+    } else {
+        market.no_mint
+    };
+    require!(
+        expected_mint != Pubkey::default() && ctx.accounts.outcome_mint.key() == expected_mint,
+        SuperfanError::InvalidOutcomeMint
+    );
+    check_entry(
+        market,
+        &ctx.accounts.config,
+        ctx.accounts.scout_registry.as_deref_mut(),
+        now,
+    )?;
+
+    let side = if side_yes {
+        MarketOutcome::Yes as u8
+    } else {
+        MarketOutcome::No as u8
+    };
+    let (shares, _) = buy(
+        market,
+        &mut ctx.accounts.liquidity_pool,
+        side,
+        amount,
+        false,
+        min_shares_out,
+    )?;
+    let token_stake = if side_yes {
+        &mut market.yes_token_stake
+    } else {
+        &mut market.no_token_stake
+    };
+    *token_stake = token_stake
+        .checked_add(amount)
+        .ok_or(SuperfanError::MathOverflow)?;
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.user_token_account.to_account_info(),
+        to: ctx.accounts.liquidity_vault.to_account_info(),
+        authority: ctx.accounts.user.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, amount)?;
+
+    let market = &ctx.accounts.market;
+    let market_id = market.market_id.to_le_bytes();
+    let seeds: &[&[u8]] = &[
+        b"market",
+        market.sponsor.as_ref(),
+        &market_id,
+        &[market.bump],
+    ];
+    let signer = &[seeds];
+    let cpi_accounts = MintTo {
+        mint: ctx.accounts.outcome_mint.to_account_info(),
+        to: ctx.accounts.user_outcome_account.to_account_info(),
+        authority: market.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer,
+    );
+    token::mint_to(cpi_ctx, shares)?;
+
+    emit!(OutcomeTokensBought {
+        market: market.key(),
+        user: ctx.accounts.user.key(),
+        side_yes,
+        amount,
+        shares,
+        implied_prob_bps: market.implied_probs_bps[Market::outcome_index(side)],
+    });
+    Ok(())
+}
+
+/// Burns outcome tokens for USDC from the pool vault: one base unit per
+/// winning token once resolved (a linear fraction of one on scalar markets),
+/// or a pro-rata share of the stake paid for that side's tokens if the market
+/// was cancelled.
+pub fn redeem_outcome_tokens(ctx: Context<RedeemOutcomeTokens>, amount: u64) -> Result<()> {
+    require!(amount > 0, SuperfanError::InvalidAmount);
+    let market = &mut ctx.accounts.market;
+    let mint = ctx.accounts.outcome_mint.key();
+    require!(
+        mint != Pubkey::default() && (mint == market.yes_mint || mint == market.no_mint),
+        SuperfanError::InvalidOutcomeMint
+    );
+    let side_yes = mint == market.yes_mint;
+    let pool = &mut ctx.accounts.liquidity_pool;
+
+    let payout = if market.status == MarketStatus::Resolved as u8 {
+        let side = if side_yes {
+            MarketOutcome::Yes as u8
+        } else {
+            MarketOutcome::No as u8
+        };
+        let payout_bps = market.payout_bps(side);
+        require!(payout_bps > 0, SuperfanError::InvalidOutcomeMint);
+        release_stakes(pool, market)?;
+        let i = Market::outcome_index(side);
+        market.outcome_payouts[i] = market.outcome_payouts[i].saturating_sub(amount);
+        let payout = bps_of(amount, payout_bps);
+        pool.capacity = pool
+            .capacity
+            .checked_sub(payout)
+            .ok_or(SuperfanError::InsufficientLiquidity)?;
+        sync_exposure(pool, market)?;
+        payout
+    } else if market.status == MarketStatus::Cancelled as u8 {
+        let supply = ctx.accounts.outcome_mint.supply;
+        require!(amount <= supply, SuperfanError::InvalidAmount);
+        let token_stake = if side_yes {
+            &mut market.yes_token_stake
+        } else {
+            &mut market.no_token_stake
+        };
+        let refund = (*token_stake as u128 * amount as u128 / supply as u128) as u64;
+        *token_stake -= refund;
+        sync_exposure(pool, market)?;
+        refund
+    } else {
+        return err!(SuperfanError::InvalidStatus);
+    };
+
+    let cpi_accounts = Burn {
+        mint: ctx.accounts.outcome_mint.to_account_info(),
+        from: ctx.accounts.user_outcome_account.to_account_info(),
+        authority: ctx.accounts.user.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::burn(cpi_ctx, amount)?;
+
+    if payout > 0 {
+        transfer_from_vault(
+            &ctx.accounts.token_program,
+            &ctx.accounts.liquidity_pool,
+            &ctx.accounts.liquidity_vault,
+            ctx.accounts.user_token_account.to_account_info(),
+            payout,
+        )?;
+    }
+
+    emit!(OutcomeTokensRedeemed {
+        market: ctx.accounts.market.key(),
+        user: ctx.accounts.user.key(),
+        side_yes,
+        amount,
+        payout,
+    });
+    Ok(())
+}
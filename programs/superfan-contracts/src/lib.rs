@@ -4,6 +4,7 @@ pub mod errors;
 pub mod events;
 pub mod contexts;
 pub mod handlers;
+pub mod lmsr;
 pub mod state;
 
 pub use crate::errors::*;
@@ -33,6 +34,15 @@ pub mod superfan_contracts {
         handlers::config::register_sponsor(ctx, name_hash)
     }
 
+    pub fn set_season_decay(ctx: Context<SetSeasonDecay>, decay_bps: u16) -> Result<()> {
+        handlers::config::set_season_decay(ctx, decay_bps)
+    }
+
+    pub fn start_season(ctx: Context<StartSeason>) -> Result<()> {
+        handlers::config::start_season(ctx)
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn create_market(
         ctx: Context<CreateMarket>,
         market_id: u64,
@@ -43,8 +53,16 @@ pub mod superfan_contracts {
         resolution_deadline: i64,
         conviction_threshold_bps: u16,
         max_pool_exposure: u64,
-        liquidity_pool: Pubkey,
         signal_oracle: Pubkey,
+        early_exit_penalty_bps: u16,
+        min_reputation: Option<i64>,
+        early_access_starts_at: Option<i64>,
+        early_access_min_reputation: i64,
+        market_kind: u8,
+        fee_bps: u16,
+        outcome_count: u8,
+        scalar_lower: u64,
+        scalar_upper: u64,
     ) -> Result<()> {
         handlers::market::create_market(
             ctx,
@@ -56,8 +74,16 @@ pub mod superfan_contracts {
             resolution_deadline,
             conviction_threshold_bps,
             max_pool_exposure,
-            liquidity_pool,
             signal_oracle,
+            early_exit_penalty_bps,
+            min_reputation,
+            early_access_starts_at,
+            early_access_min_reputation,
+            market_kind,
+            fee_bps,
+            outcome_count,
+            scalar_lower,
+            scalar_upper,
         )
     }
 
@@ -70,32 +96,120 @@ pub mod superfan_contracts {
     }
 
     pub fn resolve_market(
-        ctx: Context<UpdateMarketStatus>,
-        outcome_yes: bool,
+        ctx: Context<SponsorResolveMarket>,
+        outcome: u8,
+    ) -> Result<()> {
+        handlers::market::resolve_market(ctx, outcome)
+    }
+
+    pub fn resolve_market_by_oracle(
+        ctx: Context<OracleResolveMarket>,
+        measured_bps: i64,
     ) -> Result<()> {
-        handlers::market::resolve_market(ctx, outcome_yes)
+        handlers::market::resolve_market_by_oracle(ctx, measured_bps)
+    }
+
+    pub fn void_market_by_oracle(ctx: Context<OracleResolveMarket>) -> Result<()> {
+        handlers::market::void_market_by_oracle(ctx)
+    }
+
+    pub fn void_expired_market(ctx: Context<VoidExpiredMarket>) -> Result<()> {
+        handlers::market::void_expired_market(ctx)
+    }
+
+    pub fn resolve_scalar_market(ctx: Context<OracleResolveMarket>, value: u64) -> Result<()> {
+        handlers::market::resolve_scalar_market(ctx, value)
     }
 
     pub fn fund_pool(
         ctx: Context<FundPool>,
         capacity: u64,
         first_n_limit: u16,
+        early_bonus_bps: u16,
+        early_min_stake: u64,
     ) -> Result<()> {
-        handlers::liquidity::fund_pool(ctx, capacity, first_n_limit)
+        handlers::liquidity::fund_pool(
+            ctx,
+            capacity,
+            first_n_limit,
+            early_bonus_bps,
+            early_min_stake,
+        )
+    }
+
+    pub fn withdraw_pool(ctx: Context<WithdrawPool>, amount: u64) -> Result<()> {
+        handlers::liquidity::withdraw_pool(ctx, amount)
+    }
+
+    pub fn configure_reputation_bonus(
+        ctx: Context<ConfigureReputationBonus>,
+        budget: u64,
+        max_bonus_bps: u16,
+    ) -> Result<()> {
+        handlers::liquidity::configure_reputation_bonus(ctx, budget, max_bonus_bps)
+    }
+
+    pub fn open_position(
+        ctx: Context<OpenPosition>,
+        amount: u64,
+        outcome: u8,
+        min_shares_out: u64,
+        expires_at: i64,
+    ) -> Result<()> {
+        handlers::positions::open_position(ctx, amount, outcome, min_shares_out, expires_at)
     }
 
-    pub fn withdraw_pool(ctx: Context<WithdrawPool>) -> Result<()> {
-        handlers::liquidity::withdraw_pool(ctx)
+    pub fn increase_position(
+        ctx: Context<AdjustPosition>,
+        amount: u64,
+        min_shares_out: u64,
+        expires_at: i64,
+    ) -> Result<()> {
+        handlers::positions::increase_position(ctx, amount, min_shares_out, expires_at)
     }
 
-    pub fn open_position(ctx: Context<OpenPosition>, amount: u64) -> Result<()> {
-        handlers::positions::open_position(ctx, amount)
+    pub fn decrease_position(
+        ctx: Context<AdjustPosition>,
+        amount: u64,
+        min_payout: u64,
+    ) -> Result<()> {
+        handlers::positions::decrease_position(ctx, amount, min_payout)
+    }
+
+    pub fn transfer_position(ctx: Context<TransferPosition>) -> Result<()> {
+        handlers::positions::transfer_position(ctx)
     }
 
     pub fn close_position(ctx: Context<ClosePosition>) -> Result<()> {
         handlers::positions::close_position(ctx)
     }
 
+    pub fn claim_winnings(ctx: Context<ClaimWinnings>) -> Result<()> {
+        handlers::payouts::claim_winnings(ctx)
+    }
+
+    pub fn create_outcome_mints(ctx: Context<CreateOutcomeMints>) -> Result<()> {
+        handlers::tokens::create_outcome_mints(ctx)
+    }
+
+    pub fn buy_outcome_tokens(
+        ctx: Context<BuyOutcomeTokens>,
+        amount: u64,
+        side_yes: bool,
+        min_shares_out: u64,
+        expires_at: i64,
+    ) -> Result<()> {
+        handlers::tokens::buy_outcome_tokens(ctx, amount, side_yes, min_shares_out, expires_at)
+    }
+
+    pub fn redeem_outcome_tokens(ctx: Context<RedeemOutcomeTokens>, amount: u64) -> Result<()> {
+        handlers::tokens::redeem_outcome_tokens(ctx, amount)
+    }
+
+    pub fn init_scout(ctx: Context<InitScout>) -> Result<()> {
+        handlers::reputation::init_scout(ctx)
+    }
+
     pub fn update_reputation(
         ctx: Context<UpdateReputation>,
         delta: i64,
@@ -103,14 +217,43 @@ pub mod superfan_contracts {
         handlers::reputation::update_reputation(ctx, delta)
     }
 
-    pub fn distribute_payouts(ctx: Context<DistributePayouts>) -> Result<()> {
+    pub fn roll_scout_season(ctx: Context<RollScoutSeason>) -> Result<()> {
+        handlers::reputation::roll_scout_season(ctx)
+    }
+
+    pub fn scout_stats(ctx: Context<ReadScout>) -> Result<ScoutStats> {
+        handlers::reputation::scout_stats(ctx)
+    }
+
+    pub fn distribute_payouts<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DistributePayouts<'info>>,
+    ) -> Result<()> {
         handlers::payouts::distribute_payouts(ctx)
     }
 
     pub fn submit_signal_commitment(
         ctx: Context<SubmitSignalCommitment>,
         commitment_root: [u8; 32],
+        resolution_metric: [u8; 32],
+    ) -> Result<()> {
+        handlers::signal::submit_signal_commitment(ctx, commitment_root, resolution_metric)
+    }
+
+    pub fn verify_signal(
+        ctx: Context<VerifySignal>,
+        subject: Pubkey,
+        metric: [u8; 32],
+        value: i64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        handlers::signal::verify_signal(ctx, subject, metric, value, proof)
+    }
+
+    pub fn resolve_market_with_proof(
+        ctx: Context<ProofResolveMarket>,
+        measured_bps: i64,
+        proof: Vec<[u8; 32]>,
     ) -> Result<()> {
-        handlers::signal::submit_signal_commitment(ctx, commitment_root)
+        handlers::signal::resolve_market_with_proof(ctx, measured_bps, proof)
     }
 }
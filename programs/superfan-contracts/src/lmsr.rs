@@ -0,0 +1,216 @@
+//! Fixed-point LMSR (logarithmic market scoring rule) pricing. Share
+//! quantities, stakes and the liquidity parameter `b` are all token base
+//! units; intermediate math runs at 1e12 precision.
+//!
+//! With cost function `C(q) = b * ln(sum of e^(q_i / b))` over `n` outcomes
+//! the market maker's worst-case loss is `b * ln n`, so sizing `b` from
+//! `max_pool_exposure` bounds what the sponsor pool can lose on a market.
+
+use anchor_lang::prelude::*;
+
+use crate::errors::SuperfanError;
+use crate::state::BPS_DENOMINATOR;
+
+const FP: u128 = 1_000_000_000_000;
+const LN2: u128 = 693_147_180_560;
+/// Largest exponent `exp` accepts: e^60 * 1e12 still fits in a u128.
+const MAX_EXP: u128 = 60 * FP;
+
+/// Liquidity parameter whose worst-case loss, `b * ln(outcomes)`, equals
+/// `max_pool_exposure`.
+pub fn liquidity_for_exposure(max_pool_exposure: u64, outcomes: u8) -> u64 {
+    (max_pool_exposure as u128 * FP / ln(outcomes as u128 * FP)) as u64
+}
+
+/// Implied probability of outcome `i` winning, in bps. Saturates at 0 once
+/// the other outcomes lead by more than `MAX_EXP` (about 60 * b) in shares.
+pub fn price_bps(b: u64, q: &[u64], i: usize) -> Result<u16> {
+    let odds = log_odds_against(b, q, i)? as u128;
+    if odds > MAX_EXP {
+        return Ok(0);
+    }
+    Ok((BPS_DENOMINATOR as u128 * FP / exp(odds)?) as u16)
+}
+
+/// Shares of outcome `i` that `amount` buys, rounded down:
+/// `b * ln(1 + (1 + r) * (e^(amount / b) - 1))` with `r` the odds against `i`,
+/// evaluated in log space so it holds however far `i` trails.
+pub fn buy_shares(b: u64, q: &[u64], i: usize, amount: u64) -> Result<u64> {
+    let odds = log_odds_against(b, q, i)?;
+    let growth = exp(ratio(amount, b))? - FP;
+    if growth == 0 {
+        return Ok(0);
+    }
+    let log_growth = if growth >= FP {
+        ln(growth) as i128
+    } else {
+        -(ln(FP * FP / growth) as i128)
+    };
+    let scaled = log_sum_exp(&[0, odds + log_growth])?;
+    to_units(b, scaled.max(0) as u128)
+}
+
+/// Collateral returned for selling `shares` of outcome `i` back, rounded down:
+/// `C(q) - C(q - shares * e_i)`, evaluated in log space so a lopsided market
+/// does not lose precision.
+pub fn sell_proceeds(b: u64, q: &[u64], i: usize, shares: u64) -> Result<u64> {
+    require!(b > 0, SuperfanError::InvalidAmount);
+    require!(shares <= q[i], SuperfanError::InvalidAmount);
+    // Both costs are taken relative to `q[i] / b`, which cancels out.
+    let mut xs: Vec<i128> = q.iter().map(|q_j| signed_ratio(*q_j, q[i], b)).collect();
+    let before = log_sum_exp(&xs)?;
+    xs[i] = signed_ratio(q[i] - shares, q[i], b);
+    let after = log_sum_exp(&xs)?;
+    to_units(b, (before - after).max(0) as u128)
+}
+
+/// ln(sum of e^x) for scaled, signed `xs`.
+fn log_sum_exp(xs: &[i128]) -> Result<i128> {
+    let max = xs.iter().copied().max().unwrap_or(0);
+    let mut sum = 0;
+    for x in xs {
+        let gap = max.abs_diff(*x);
+        if gap <= MAX_EXP {
+            sum += FP * FP / exp(gap)?;
+        }
+    }
+    Ok(max + ln(sum) as i128)
+}
+
+fn signed_ratio(value: u64, offset: u64, b: u64) -> i128 {
+    (value as i128 - offset as i128) * FP as i128 / b as i128
+}
+
+/// `ln(1 + r)` with `r = sum over j != i of e^((q_j - q_i) / b)`, the odds
+/// against outcome `i`. Never negative, since the `j == i` term is e^0.
+fn log_odds_against(b: u64, q: &[u64], i: usize) -> Result<i128> {
+    require!(b > 0, SuperfanError::InvalidAmount);
+    let xs: Vec<i128> = q.iter().map(|q_j| signed_ratio(*q_j, q[i], b)).collect();
+    log_sum_exp(&xs)
+}
+
+fn ratio(value: u64, b: u64) -> u128 {
+    value as u128 * FP / b as u128
+}
+
+fn to_units(b: u64, scaled: u128) -> Result<u64> {
+    let units = (b as u128)
+        .checked_mul(scaled)
+        .ok_or(SuperfanError::MathOverflow)?
+        / FP;
+    u64::try_from(units).map_err(|_| error!(SuperfanError::MathOverflow))
+}
+
+/// e^x for scaled `x >= 0`: split off powers of two, Taylor series for the rest.
+fn exp(x: u128) -> Result<u128> {
+    require!(x <= MAX_EXP, SuperfanError::MathOverflow);
+    let k = x / LN2;
+    let r = x - k * LN2;
+    let mut term = FP;
+    let mut sum = FP;
+    for i in 1..=24u128 {
+        term = term * r / FP / i;
+        if term == 0 {
+            break;
+        }
+        sum += term;
+    }
+    Ok(sum << k)
+}
+
+/// ln(y) for scaled `y >= 1`: split off powers of two, atanh series for the rest.
+fn ln(y: u128) -> u128 {
+    let k = 127 - (y / FP).leading_zeros() as u128;
+    let m = y >> k;
+    let s = (m - FP) * FP / (m + FP);
+    let s2 = s * s / FP;
+    let mut term = s;
+    let mut sum = 0;
+    let mut n = 1;
+    while term > 0 {
+        sum += term / n;
+        term = term * s2 / FP;
+        n += 2;
+    }
+    k * LN2 + 2 * sum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPOSURE: u64 = 1_000_000_000; // 1,000 USDC
+
+    #[test]
+    fn prices_are_symmetric() {
+        let b = liquidity_for_exposure(EXPOSURE, 2);
+        assert_eq!(price_bps(b, &[0, 0], 0).unwrap(), 5_000);
+        assert_eq!(price_bps(b, &[0, 0], 1).unwrap(), 5_000);
+
+        let q = [3 * b / 2, b / 4];
+        let (p0, p1) = (price_bps(b, &q, 0).unwrap(), price_bps(b, &q, 1).unwrap());
+        assert_eq!(price_bps(b, &[q[1], q[0]], 1).unwrap(), p0);
+        assert_eq!(price_bps(b, &[q[1], q[0]], 0).unwrap(), p1);
+        assert!((9_998..=10_000).contains(&(p0 + p1)));
+
+        let b = liquidity_for_exposure(EXPOSURE, 4);
+        for i in 0..4 {
+            assert_eq!(price_bps(b, &[7, 7, 7, 7], i).unwrap(), 2_500);
+        }
+    }
+
+    #[test]
+    fn selling_back_never_returns_more_than_the_buy_cost() {
+        let b = liquidity_for_exposure(EXPOSURE, 3);
+        for (q, amount) in [
+            ([0, 0, 0], 1),
+            ([0, 0, 0], 50_000_000),
+            ([5 * b, 0, b], 250_000_000),
+            ([0, 20 * b, 3 * b], 10_000_000),
+        ] {
+            let shares = buy_shares(b, &q, 0, amount).unwrap();
+            let mut after = q;
+            after[0] += shares;
+            let proceeds = sell_proceeds(b, &after, 0, shares).unwrap();
+            assert!(proceeds <= amount, "{q:?}: paid {amount}, got back {proceeds}");
+            assert!(amount - proceeds <= 2 + amount / 1_000_000, "{q:?}: lost {proceeds}");
+        }
+    }
+
+    #[test]
+    fn worst_case_loss_is_bounded_by_max_pool_exposure() {
+        for outcomes in [2u8, 3, 8] {
+            let b = liquidity_for_exposure(EXPOSURE, outcomes);
+            let mut q = vec![0u64; outcomes as usize];
+            let mut collected = 0u64;
+            // Keep buying the same outcome until it is a near-certainty.
+            while price_bps(b, &q, 0).unwrap() < 9_999 {
+                let amount = EXPOSURE / 10;
+                q[0] += buy_shares(b, &q, 0, amount).unwrap();
+                collected += amount;
+            }
+            let loss = q[0].saturating_sub(collected);
+            assert!(loss <= EXPOSURE, "{outcomes} outcomes: lost {loss}");
+            assert!(loss > EXPOSURE * 9 / 10, "{outcomes} outcomes: lost only {loss}");
+        }
+    }
+
+    #[test]
+    fn prices_saturate_past_max_exp() {
+        let b = liquidity_for_exposure(EXPOSURE, 2);
+        let lead = (MAX_EXP / FP) as u64 * b;
+        for q in [[0, lead - b], [0, lead + b], [0, 200 * b]] {
+            let trailing = price_bps(b, &q, 0).unwrap();
+            assert!(trailing <= 1, "{q:?}: {trailing}");
+            assert_eq!(price_bps(b, &q, 1).unwrap(), 10_000);
+
+            // Both sides stay tradable.
+            let shares = buy_shares(b, &q, 0, 1_000_000).unwrap();
+            assert!(shares > 1_000_000);
+            let shares = buy_shares(b, &q, 1, 1_000_000).unwrap();
+            assert!((999_998..=1_000_000).contains(&shares), "{q:?}: {shares}");
+            assert!(sell_proceeds(b, &q, 1, b).unwrap() <= b);
+        }
+        assert_eq!(price_bps(b, &[0, 200 * b], 0).unwrap(), 0);
+    }
+}
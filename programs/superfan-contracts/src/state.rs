@@ -1,15 +1,34 @@
 use anchor_lang::prelude::*;
 
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Stake that earns one reputation point (1 USDC at 6 decimals).
+pub const REPUTATION_STAKE_UNIT: u64 = 1_000_000;
+
+/// Minimum `ScoutRegistry::score` for reputation tiers 1..=3.
+pub const SCOUT_TIER_THRESHOLDS: [i64; 3] = [50, 250, 1_000];
+
+/// Most outcomes a categorical market can declare.
+pub const MAX_OUTCOMES: usize = 8;
+
+/// `amount * bps / 10_000`, rounded down.
+pub fn bps_of(amount: u64, bps: u16) -> u64 {
+    (amount as u128 * bps as u128 / BPS_DENOMINATOR as u128) as u64
+}
+
 #[account]
 pub struct SuperfanConfig {
     pub admin: Pubkey,
     pub usdc_mint: Pubkey,
     pub max_sponsors: u32,
+    pub current_season: u32,
+    pub season_started_at: i64,
+    pub season_decay_bps: u16, // lifetime score decay applied per elapsed season
     pub bump: u8,
 }
 
 impl SuperfanConfig {
-    pub const SPACE: usize = 32 + 32 + 4 + 1;
+    pub const SPACE: usize = 32 + 32 + 4 + 4 + 8 + 2 + 1;
 }
 
 #[account]
@@ -47,14 +66,97 @@ pub struct Market {
     pub max_pool_exposure: u64,
     pub liquidity_pool: Pubkey,
     pub signal_oracle: Pubkey,
+    pub early_exit_penalty_bps: u16,
+    pub min_reputation: Option<i64>,
+    pub early_access_starts_at: Option<i64>,
+    pub early_access_min_reputation: i64,
+    pub market_kind: u8,
+    pub fee_bps: u16, // parimutuel only: taken from the losing side for the pool
+    pub outcome_count: u8, // 2 for YES/NO markets, up to `MAX_OUTCOMES`
     pub status: u8,
-    pub outcome: u8, // 0 = unresolved, 1 = yes, 2 = no
+    pub outcome: u8, // 0 = unresolved, else 1..=outcome_count (1 = yes, 2 = no)
     pub resolved_at: i64,
+    pub outcome_totals: [u64; MAX_OUTCOMES], // stake per outcome, indexed by outcome - 1
+    pub pool_exposure: u64,
+    pub measured_bps: i64, // signal metric reported at resolution, if any
+    pub early_supporter_count: u32, // positions that took an early-supporter slot
+    pub lmsr_b: u64, // LMSR liquidity parameter, sized from `max_pool_exposure`
+    pub outcome_shares: [u64; MAX_OUTCOMES],
+    pub outcome_payouts: [u64; MAX_OUTCOMES], // owed to holders if that outcome wins
+    pub implied_probs_bps: [u16; MAX_OUTCOMES],
+    pub stakes_released: bool, // stakes moved into pool capacity after resolution
+    pub yes_mint: Pubkey, // outcome token mints; default until `create_outcome_mints`
+    pub no_mint: Pubkey,
+    pub yes_token_stake: u64, // stake paid for outstanding outcome tokens
+    pub no_token_stake: u64,
+    pub scalar_lower: u64, // scalar markets only; both zero otherwise
+    pub scalar_upper: u64,
+    pub scalar_value: u64, // oracle value at resolution, clamped to the range
+    pub long_payout_bps: u16, // share of face value paid to LONG (outcome 1)
     pub bump: u8,
 }
 
 impl Market {
-    pub const SPACE: usize = 32 + 8 + 32 + 32 + 8 + 8 + 8 + 2 + 8 + 32 + 32 + 1 + 1 + 1 + 8;
+    pub const SPACE: usize = 32 + 8 + 32 + 32 + 8 + 8 + 8 + 2 + 8 + 32 + 32
+        + 2 + 9 + 9 + 8
+        + 1 + 2 + 1
+        + 1 + 1 + 8
+        + 8 * MAX_OUTCOMES + 8 + 8 + 4
+        + 8 + 8 * MAX_OUTCOMES + 8 * MAX_OUTCOMES + 2 * MAX_OUTCOMES + 1
+        + 32 + 32 + 8 + 8
+        + 8 + 8 + 8 + 2
+        + 1;
+
+    /// Slot in the per-outcome arrays for outcome `1..=outcome_count`.
+    pub fn outcome_index(outcome: u8) -> usize {
+        outcome as usize - 1
+    }
+
+    /// Scalar markets pay LONG (outcome 1) and SHORT (outcome 2) linearly on
+    /// where the resolved value falls between `scalar_lower` and `scalar_upper`.
+    pub fn is_scalar(&self) -> bool {
+        self.scalar_upper > self.scalar_lower
+    }
+
+    /// Share of a position's face value (`amount + winnings`) paid out on
+    /// `outcome` once resolved: all or nothing, or the linear split on scalars.
+    pub fn payout_bps(&self, outcome: u8) -> u16 {
+        if self.is_scalar() {
+            if outcome == MarketOutcome::Yes as u8 {
+                self.long_payout_bps
+            } else {
+                BPS_DENOMINATOR as u16 - self.long_payout_bps
+            }
+        } else if outcome == self.outcome {
+            BPS_DENOMINATOR as u16
+        } else {
+            0
+        }
+    }
+
+    pub fn total_staked(&self) -> u64 {
+        self.outcome_totals.iter().fold(0u64, |sum, total| sum.saturating_add(*total))
+    }
+
+    /// Pool capital the market can still draw on. While trading this is the
+    /// worst-case payout not already covered by stakes; once resolved and the
+    /// stakes have been released to the pool, it is what holders are still owed.
+    /// Parimutuel markets pay winners out of stakes and never draw on the pool.
+    pub fn liability(&self) -> u64 {
+        if self.market_kind == MarketKind::Parimutuel as u8
+            || self.status == MarketStatus::Cancelled as u8
+        {
+            return 0;
+        }
+        if self.stakes_released {
+            return (1..=self.outcome_count).fold(0u64, |owed, outcome| {
+                let face = self.outcome_payouts[Market::outcome_index(outcome)];
+                owed.saturating_add(bps_of(face, self.payout_bps(outcome)))
+            });
+        }
+        let worst_case = self.outcome_payouts.iter().copied().max().unwrap_or(0);
+        worst_case.saturating_sub(self.total_staked())
+    }
 }
 
 #[account]
@@ -63,48 +165,132 @@ pub struct LiquidityPool {
     pub capacity: u64,
     pub locked: u64,
     pub first_n_limit: u16,
+    pub early_bonus_bps: u16,
+    pub early_min_stake: u64, // smallest opening stake that takes an early slot
+    pub reputation_bonus_budget: u64, // reserved out of capacity for scout bonuses
+    pub reputation_bonus_max_bps: u16,
     pub vault: Pubkey,
     pub bump: u8,
 }
 
 impl LiquidityPool {
-    pub const SPACE: usize = 32 + 8 + 8 + 2 + 32 + 1;
+    pub const SPACE: usize = 32 + 8 + 8 + 2 + 2 + 8 + 8 + 2 + 32 + 1;
+
+    /// Sponsor capital neither backing an open position nor reserved for
+    /// reputation bonuses.
+    pub fn available(&self) -> u64 {
+        self.capacity
+            .saturating_sub(self.locked)
+            .saturating_sub(self.reputation_bonus_budget)
+    }
 }
 
 #[account]
 pub struct Position {
     pub user: Pubkey,
     pub market: Pubkey,
+    pub side: u8, // outcome backed, 1..=outcome_count (matches Market::outcome)
     pub amount: u64,
+    pub winnings: u64, // paid by the pool on top of `amount` if `side` wins
+    pub early_supporter: bool,
+    pub early_shares: u64, // opening shares that earn the early-supporter bonus
+    pub entry_prob_bps: u16, // market price of `side` when opened
+    pub shares: u64,
     pub created_at: i64,
+    pub settled: bool,
     pub bump: u8,
 }
 
 impl Position {
-    pub const SPACE: usize = 32 + 32 + 8 + 8 + 1;
+    pub const SPACE: usize = 32 + 32 + 1 + 8 + 8 + 1 + 8 + 2 + 8 + 8 + 1 + 1;
 }
 
 #[account]
 pub struct ScoutRegistry {
     pub user: Pubkey,
     pub score: i64,
+    pub markets_participated: u32,
+    pub wins: u32,
+    pub losses: u32,
+    pub cumulative_stake: u64,
+    pub brier_sum_bps: u64, // sum of per-market Brier scores, each in bps
+    pub season: u32,
+    pub season_score: i64,
+    pub last_season_score: i64,
     pub bump: u8,
 }
 
 impl ScoutRegistry {
-    pub const SPACE: usize = 32 + 8 + 1;
+    pub const SPACE: usize = 32 + 8 + 4 + 4 + 4 + 8 + 8 + 4 + 8 + 8 + 1;
+
+    /// 0 for unproven scouts, up to 3 per `SCOUT_TIER_THRESHOLDS`.
+    pub fn tier(&self) -> u8 {
+        SCOUT_TIER_THRESHOLDS
+            .iter()
+            .filter(|threshold| self.score >= **threshold)
+            .count() as u8
+    }
+
+    pub fn win_rate_bps(&self) -> u16 {
+        if self.markets_participated == 0 {
+            return 0;
+        }
+        (self.wins as u64 * BPS_DENOMINATOR / self.markets_participated as u64) as u16
+    }
+
+    /// `10_000 - mean Brier score`: 10_000 is perfectly calibrated, 0 is always
+    /// confidently wrong.
+    pub fn calibration_bps(&self) -> u16 {
+        if self.markets_participated == 0 {
+            return 0;
+        }
+        let mean_brier = self.brier_sum_bps / self.markets_participated as u64;
+        BPS_DENOMINATOR.saturating_sub(mean_brier) as u16
+    }
+
+    pub fn stats(&self) -> ScoutStats {
+        ScoutStats {
+            user: self.user,
+            score: self.score,
+            season: self.season,
+            season_score: self.season_score,
+            tier: self.tier(),
+            markets_participated: self.markets_participated,
+            wins: self.wins,
+            losses: self.losses,
+            cumulative_stake: self.cumulative_stake,
+            win_rate_bps: self.win_rate_bps(),
+            calibration_bps: self.calibration_bps(),
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ScoutStats {
+    pub user: Pubkey,
+    pub score: i64,
+    pub season: u32,
+    pub season_score: i64,
+    pub tier: u8,
+    pub markets_participated: u32,
+    pub wins: u32,
+    pub losses: u32,
+    pub cumulative_stake: u64,
+    pub win_rate_bps: u16,
+    pub calibration_bps: u16,
 }
 
 #[account]
 pub struct SignalCommitment {
     pub market: Pubkey,
     pub commitment_root: [u8; 32],
+    pub resolution_metric: [u8; 32], // metric tag of the leaf that resolves the market
     pub recorded_at: i64,
     pub bump: u8,
 }
 
 impl SignalCommitment {
-    pub const SPACE: usize = 32 + 32 + 8 + 1;
+    pub const SPACE: usize = 32 + 32 + 32 + 8 + 1;
 }
 
 #[repr(u8)]
@@ -114,3 +300,17 @@ pub enum MarketStatus {
     Resolved = 2,
     Cancelled = 3,
 }
+
+#[repr(u8)]
+pub enum MarketKind {
+    SponsorBacked = 0, // LMSR-priced shares, subsidised by the sponsor pool
+    Parimutuel = 1,    // winners split the losing side; pool only funds bonuses
+}
+
+#[repr(u8)]
+pub enum MarketOutcome {
+    Unresolved = 0,
+    Yes = 1,
+    No = 2,
+    Invalid = 255, // voided: every stake is refunded
+}
@@ -33,6 +33,24 @@ export const deriveMarketCounterPda = (
     programId
   );
 
+export const deriveLiquidityPoolPda = (
+  sponsor: PublicKey,
+  programId: PublicKey
+): [PublicKey, number] =>
+  PublicKey.findProgramAddressSync(
+    [Buffer.from("liquidity_pool"), sponsor.toBuffer()],
+    programId
+  );
+
+export const deriveSignalCommitPda = (
+  market: PublicKey,
+  programId: PublicKey
+): [PublicKey, number] =>
+  PublicKey.findProgramAddressSync(
+    [Buffer.from("signal"), market.toBuffer()],
+    programId
+  );
+
 export const deriveMarketPda = (
   sponsor: PublicKey,
   marketId: anchor.BN,
@@ -99,8 +117,16 @@ export class SuperfanClient {
     resolutionDeadline: number;
     convictionThresholdBps: number;
     maxPoolExposure: anchor.BN;
-    liquidityPool: PublicKey;
     signalOracle: PublicKey;
+    earlyExitPenaltyBps?: number;
+    minReputation?: anchor.BN | null;
+    earlyAccessStartsAt?: number | null;
+    earlyAccessMinReputation?: anchor.BN;
+    marketKind?: number; // 0 = sponsor-backed (default), 1 = parimutuel
+    feeBps?: number;
+    outcomeCount?: number; // 2 for YES/NO markets
+    scalarLower?: anchor.BN; // set both bounds for a LONG/SHORT scalar market
+    scalarUpper?: anchor.BN;
   }) {
     const sponsor = deriveSponsorPda(params.authority, this.program.programId)[0];
     const [market] = deriveMarketPda(
@@ -124,8 +150,18 @@ export class SuperfanClient {
         new anchor.BN(params.resolutionDeadline),
         params.convictionThresholdBps,
         params.maxPoolExposure,
-        params.liquidityPool,
-        params.signalOracle
+        params.signalOracle,
+        params.earlyExitPenaltyBps ?? 0,
+        params.minReputation ?? null,
+        params.earlyAccessStartsAt != null
+          ? new anchor.BN(params.earlyAccessStartsAt)
+          : null,
+        params.earlyAccessMinReputation ?? new anchor.BN(0),
+        params.marketKind ?? 0,
+        params.feeBps ?? 0,
+        params.outcomeCount ?? 2,
+        params.scalarLower ?? new anchor.BN(0),
+        params.scalarUpper ?? new anchor.BN(0)
       )
       .accounts({
         config,
@@ -133,6 +169,8 @@ export class SuperfanClient {
         sponsor,
         marketCounter,
         market,
+        // Markets can only draw on their own sponsor's pool.
+        liquidityPool: deriveLiquidityPoolPda(sponsor, this.program.programId)[0],
         systemProgram: anchor.web3.SystemProgram.programId,
       } as any)
       .rpc();
@@ -168,17 +206,82 @@ export class SuperfanClient {
       .rpc();
   }
 
-  async resolveMarket(authority: PublicKey, market: PublicKey, outcomeYes: boolean) {
+  /** `outcome` is 1..=outcomeCount (1 = YES, 2 = NO), or 255 to void the market. */
+  async resolveMarket(authority: PublicKey, market: PublicKey, outcome: number) {
     const [config] = deriveConfigPda(this.program.programId);
     const sponsor = deriveSponsorPda(authority, this.program.programId)[0];
+    // Voiding releases the market's reservation on its liquidity pool.
+    const liquidityPool =
+      outcome === 255 ? await this.marketLiquidityPool(market) : null;
     await this.program.methods
-      .resolveMarket(outcomeYes)
+      .resolveMarket(outcome)
       .accounts({
         config,
         authority,
         sponsor,
         market,
+        signalCommit: deriveSignalCommitPda(market, this.program.programId)[0],
+        liquidityPool,
       } as any)
       .rpc();
   }
+
+  async resolveMarketByOracle(
+    oracle: PublicKey,
+    market: PublicKey,
+    measuredBps: anchor.BN
+  ) {
+    const [config] = deriveConfigPda(this.program.programId);
+    await this.program.methods
+      .resolveMarketByOracle(measuredBps)
+      .accounts({
+        config,
+        oracle,
+        market,
+      } as any)
+      .rpc();
+  }
+
+  async resolveScalarMarket(oracle: PublicKey, market: PublicKey, value: anchor.BN) {
+    const [config] = deriveConfigPda(this.program.programId);
+    await this.program.methods
+      .resolveScalarMarket(value)
+      .accounts({
+        config,
+        oracle,
+        market,
+      } as any)
+      .rpc();
+  }
+
+  async voidMarketByOracle(oracle: PublicKey, market: PublicKey) {
+    const [config] = deriveConfigPda(this.program.programId);
+    await this.program.methods
+      .voidMarketByOracle()
+      .accounts({
+        config,
+        oracle,
+        market,
+        liquidityPool: await this.marketLiquidityPool(market),
+      } as any)
+      .rpc();
+  }
+
+  /** Voids a market nobody resolved by its deadline; any wallet may call it. */
+  async voidExpiredMarket(caller: PublicKey, market: PublicKey) {
+    const [config] = deriveConfigPda(this.program.programId);
+    await this.program.methods
+      .voidExpiredMarket()
+      .accounts({
+        config,
+        caller,
+        market,
+        liquidityPool: await this.marketLiquidityPool(market),
+      } as any)
+      .rpc();
+  }
+
+  private async marketLiquidityPool(market: PublicKey) {
+    return (await this.program.account.market.fetch(market)).liquidityPool;
+  }
 }
@@ -1,9 +1,31 @@
 
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
-import { PublicKey, SystemProgram, Keypair } from "@solana/web3.js";
+import {
+  PublicKey,
+  SystemProgram,
+  Keypair,
+  LAMPORTS_PER_SOL,
+  Transaction,
+  TransactionInstruction,
+} from "@solana/web3.js";
+import { assert } from "chai";
+import { createHash } from "crypto";
 import { SuperfanContracts } from "../target/types/superfan_contracts";
 
+const { TOKEN_PROGRAM_ID, ASSOCIATED_PROGRAM_ID } = anchor.utils.token;
+
+const USDC_DECIMALS = 6;
+const usdc = (whole: number) =>
+  new anchor.BN(whole).mul(new anchor.BN(10 ** USDC_DECIMALS));
+
+const YES = 1;
+const NO = 2;
+const INVALID = 255;
+const RESOLVED = 2;
+const CANCELLED = 3;
+const ORDER_EXPIRY = new anchor.BN(4_000_000_000);
+
 describe("superfan-contracts: market factory", () => {
   // Configure the client to use the local cluster.
   const provider = anchor.AnchorProvider.env();
@@ -15,6 +37,8 @@ describe("superfan-contracts: market factory", () => {
   let configPda: PublicKey;
   let sponsorPda: PublicKey;
   let marketCounterPda: PublicKey;
+  let liquidityPoolPda: PublicKey;
+  let usdcMint: PublicKey;
 
   // Simple helper to create a 32-byte hash from a string
   const hash32 = (input: string): number[] => {
@@ -26,6 +50,99 @@ describe("superfan-contracts: market factory", () => {
     return out;
   };
 
+  // Minimal SPL token helpers, so the tests don't need @solana/spl-token.
+  const createMint = async (decimals: number): Promise<PublicKey> => {
+    const mint = Keypair.generate();
+    const space = 82;
+    const lamports =
+      await provider.connection.getMinimumBalanceForRentExemption(space);
+    const initializeMint2 = new TransactionInstruction({
+      programId: TOKEN_PROGRAM_ID,
+      keys: [{ pubkey: mint.publicKey, isSigner: false, isWritable: true }],
+      // decimals, mint authority, no freeze authority
+      data: Buffer.concat([
+        Buffer.from([20, decimals]),
+        provider.wallet.publicKey.toBuffer(),
+        Buffer.from([0]),
+      ]),
+    });
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: provider.wallet.publicKey,
+          newAccountPubkey: mint.publicKey,
+          space,
+          lamports,
+          programId: TOKEN_PROGRAM_ID,
+        }),
+        initializeMint2
+      ),
+      [mint]
+    );
+    return mint.publicKey;
+  };
+
+  const createAta = async (
+    mint: PublicKey,
+    owner: PublicKey
+  ): Promise<PublicKey> => {
+    const ata = anchor.utils.token.associatedAddress({ mint, owner });
+    const createIdempotent = new TransactionInstruction({
+      programId: ASSOCIATED_PROGRAM_ID,
+      keys: [
+        { pubkey: provider.wallet.publicKey, isSigner: true, isWritable: true },
+        { pubkey: ata, isSigner: false, isWritable: true },
+        { pubkey: owner, isSigner: false, isWritable: false },
+        { pubkey: mint, isSigner: false, isWritable: false },
+        { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+        { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+      ],
+      data: Buffer.from([1]),
+    });
+    await provider.sendAndConfirm(new Transaction().add(createIdempotent));
+    return ata;
+  };
+
+  const mintTo = async (
+    mint: PublicKey,
+    destination: PublicKey,
+    amount: anchor.BN
+  ) => {
+    const mintToIx = new TransactionInstruction({
+      programId: TOKEN_PROGRAM_ID,
+      keys: [
+        { pubkey: mint, isSigner: false, isWritable: true },
+        { pubkey: destination, isSigner: false, isWritable: true },
+        {
+          pubkey: provider.wallet.publicKey,
+          isSigner: true,
+          isWritable: false,
+        },
+      ],
+      data: Buffer.concat([
+        Buffer.from([7]),
+        amount.toArrayLike(Buffer, "le", 8),
+      ]),
+    });
+    await provider.sendAndConfirm(new Transaction().add(mintToIx));
+  };
+
+  const balance = async (tokenAccount: PublicKey): Promise<anchor.BN> =>
+    new anchor.BN(
+      (await provider.connection.getTokenAccountBalance(tokenAccount)).value
+        .amount
+    );
+
+  const expectError = async (call: Promise<unknown>, code: string) => {
+    try {
+      await call;
+    } catch (err: any) {
+      assert.equal(err.error?.errorCode?.code, code, err.toString());
+      return;
+    }
+    assert.fail(`Expected ${code}`);
+  };
+
   before(async () => {
     // Derive the global config PDA
     [configPda] = PublicKey.findProgramAddressSync(
@@ -33,7 +150,7 @@ describe("superfan-contracts: market factory", () => {
       program.programId
     );
 
-    const usdcMint = Keypair.generate().publicKey;
+    usdcMint = await createMint(USDC_DECIMALS);
     const admin = provider.wallet.publicKey;
 
     // Initialize global config
@@ -60,6 +177,11 @@ describe("superfan-contracts: market factory", () => {
       program.programId
     );
 
+    [liquidityPoolPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("liquidity_pool"), sponsorPda.toBuffer()],
+      program.programId
+    );
+
     const nameHash = hash32("Test Label");
 
     await program.methods
@@ -90,12 +212,20 @@ describe("superfan-contracts: market factory", () => {
 
     const convictionThresholdBps = 2000; // 20%
     const maxPoolExposure = new anchor.BN(25_000_000_000); // 25k with 6 decimals (example)
+    const earlyExitPenaltyBps = 100; // 1%
+    const minReputation = null; // open to every fan
+    const earlyAccessStartsAt = null; // no early-access window
+    const earlyAccessMinReputation = new anchor.BN(0);
+    const marketKind = 0; // sponsor-backed
+    const feeBps = 0;
+    const outcomeCount = 2; // YES/NO
+    const scalarLower = new anchor.BN(0); // not a scalar market
+    const scalarUpper = new anchor.BN(0);
 
     const marketId = new anchor.BN(1);
     const artistWallet = Keypair.generate().publicKey;
     const artistIdHash = hash32("artist-123");
 
-    const liquidityPool = Keypair.generate().publicKey;
     const signalOracle = Keypair.generate().publicKey;
 
     const [marketPda] = PublicKey.findProgramAddressSync(
@@ -117,8 +247,16 @@ describe("superfan-contracts: market factory", () => {
         new anchor.BN(resolutionDeadline),
         convictionThresholdBps,
         maxPoolExposure,
-        liquidityPool,
-        signalOracle
+        signalOracle,
+        earlyExitPenaltyBps,
+        minReputation,
+        earlyAccessStartsAt,
+        earlyAccessMinReputation,
+        marketKind,
+        feeBps,
+        outcomeCount,
+        scalarLower,
+        scalarUpper
       )
       .accounts({
         config: configPda,
@@ -126,6 +264,7 @@ describe("superfan-contracts: market factory", () => {
         sponsor: sponsorPda,
         marketCounter: marketCounterPda,
         market: marketPda,
+        liquidityPool: liquidityPoolPda,
         systemProgram: SystemProgram.programId,
       })
       .rpc();
@@ -152,11 +291,19 @@ describe("superfan-contracts: market factory", () => {
 
     const convictionThresholdBps = 1500;
     const maxPoolExposure = new anchor.BN(10_000_000_000);
+    const earlyExitPenaltyBps = 100; // 1%
+    const minReputation = null; // open to every fan
+    const earlyAccessStartsAt = null; // no early-access window
+    const earlyAccessMinReputation = new anchor.BN(0);
+    const marketKind = 0; // sponsor-backed
+    const feeBps = 0;
+    const outcomeCount = 2; // YES/NO
+    const scalarLower = new anchor.BN(0); // not a scalar market
+    const scalarUpper = new anchor.BN(0);
 
     const marketId = new anchor.BN(2);
     const artistWallet = Keypair.generate().publicKey;
     const artistIdHash = hash32("artist-456");
-    const liquidityPool = Keypair.generate().publicKey;
     const signalOracle = Keypair.generate().publicKey;
 
     const [marketPda] = PublicKey.findProgramAddressSync(
@@ -178,8 +325,16 @@ describe("superfan-contracts: market factory", () => {
         new anchor.BN(resolutionDeadline),
         convictionThresholdBps,
         maxPoolExposure,
-        liquidityPool,
-        signalOracle
+        signalOracle,
+        earlyExitPenaltyBps,
+        minReputation,
+        earlyAccessStartsAt,
+        earlyAccessMinReputation,
+        marketKind,
+        feeBps,
+        outcomeCount,
+        scalarLower,
+        scalarUpper
       )
       .accounts({
         config: configPda,
@@ -187,6 +342,7 @@ describe("superfan-contracts: market factory", () => {
         sponsor: sponsorPda,
         marketCounter: marketCounterPda,
         market: marketPda,
+        liquidityPool: liquidityPoolPda,
         systemProgram: SystemProgram.programId,
       })
       .rpc();
@@ -218,11 +374,19 @@ describe("superfan-contracts: market factory", () => {
 
     const convictionThresholdBps = 1000;
     const maxPoolExposure = new anchor.BN(5_000_000_000);
+    const earlyExitPenaltyBps = 100; // 1%
+    const minReputation = null; // open to every fan
+    const earlyAccessStartsAt = null; // no early-access window
+    const earlyAccessMinReputation = new anchor.BN(0);
+    const marketKind = 0; // sponsor-backed
+    const feeBps = 0;
+    const outcomeCount = 2; // YES/NO
+    const scalarLower = new anchor.BN(0); // not a scalar market
+    const scalarUpper = new anchor.BN(0);
 
     const marketId = new anchor.BN(3);
     const artistWallet = Keypair.generate().publicKey;
     const artistIdHash = hash32("artist-789");
-    const liquidityPool = Keypair.generate().publicKey;
     const signalOracle = Keypair.generate().publicKey;
 
     const [marketPda] = PublicKey.findProgramAddressSync(
@@ -244,8 +408,16 @@ describe("superfan-contracts: market factory", () => {
         new anchor.BN(resolutionDeadline),
         convictionThresholdBps,
         maxPoolExposure,
-        liquidityPool,
-        signalOracle
+        signalOracle,
+        earlyExitPenaltyBps,
+        minReputation,
+        earlyAccessStartsAt,
+        earlyAccessMinReputation,
+        marketKind,
+        feeBps,
+        outcomeCount,
+        scalarLower,
+        scalarUpper
       )
       .accounts({
         config: configPda,
@@ -253,6 +425,7 @@ describe("superfan-contracts: market factory", () => {
         sponsor: sponsorPda,
         marketCounter: marketCounterPda,
         market: marketPda,
+        liquidityPool: liquidityPoolPda,
         systemProgram: SystemProgram.programId,
       })
       .rpc();
@@ -283,11 +456,19 @@ describe("superfan-contracts: market factory", () => {
 
     const convictionThresholdBps = 1200;
     const maxPoolExposure = new anchor.BN(5_000_000_000);
+    const earlyExitPenaltyBps = 100; // 1%
+    const minReputation = null; // open to every fan
+    const earlyAccessStartsAt = null; // no early-access window
+    const earlyAccessMinReputation = new anchor.BN(0);
+    const marketKind = 0; // sponsor-backed
+    const feeBps = 0;
+    const outcomeCount = 2; // YES/NO
+    const scalarLower = new anchor.BN(0); // not a scalar market
+    const scalarUpper = new anchor.BN(0);
 
     const marketId = new anchor.BN(4);
     const artistWallet = Keypair.generate().publicKey;
     const artistIdHash = hash32("artist-too-early");
-    const liquidityPool = Keypair.generate().publicKey;
     const signalOracle = Keypair.generate().publicKey;
 
     const [marketPda] = PublicKey.findProgramAddressSync(
@@ -309,8 +490,16 @@ describe("superfan-contracts: market factory", () => {
         new anchor.BN(resolutionDeadline),
         convictionThresholdBps,
         maxPoolExposure,
-        liquidityPool,
-        signalOracle
+        signalOracle,
+        earlyExitPenaltyBps,
+        minReputation,
+        earlyAccessStartsAt,
+        earlyAccessMinReputation,
+        marketKind,
+        feeBps,
+        outcomeCount,
+        scalarLower,
+        scalarUpper
       )
       .accounts({
         config: configPda,
@@ -318,6 +507,7 @@ describe("superfan-contracts: market factory", () => {
         sponsor: sponsorPda,
         marketCounter: marketCounterPda,
         market: marketPda,
+        liquidityPool: liquidityPoolPda,
         systemProgram: SystemProgram.programId,
       })
       .rpc();
@@ -342,4 +532,1365 @@ describe("superfan-contracts: market factory", () => {
       throw new Error("Expected lockMarket to fail when called too early");
     }
   });
+
+  describe("trading and settlement", () => {
+    let liquidityVault: PublicKey;
+
+    type Fan = { kp: Keypair; usdcAccount: PublicKey };
+
+    const chainTime = async (): Promise<number> => {
+      const slot = await provider.connection.getSlot();
+      return (await provider.connection.getBlockTime(slot)) ?? 0;
+    };
+
+    const waitUntilAfter = async (timestamp: anchor.BN) => {
+      while ((await chainTime()) <= timestamp.toNumber()) {
+        await new Promise((resolve) => setTimeout(resolve, 500));
+      }
+    };
+
+    const waitForTradingToClose = async (market: PublicKey) => {
+      const { tradingEndsAt } = await program.account.market.fetch(market);
+      await waitUntilAfter(tradingEndsAt);
+    };
+
+    const newFan = async (whole: number): Promise<Fan> => {
+      const kp = Keypair.generate();
+      const signature = await provider.connection.requestAirdrop(
+        kp.publicKey,
+        LAMPORTS_PER_SOL
+      );
+      const latest = await provider.connection.getLatestBlockhash();
+      await provider.connection.confirmTransaction({ signature, ...latest });
+      const usdcAccount = await createAta(usdcMint, kp.publicKey);
+      await mintTo(usdcMint, usdcAccount, usdc(whole));
+      return { kp, usdcAccount };
+    };
+
+    const positionPda = (market: PublicKey, fan: Fan) =>
+      PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("position"),
+          market.toBuffer(),
+          fan.kp.publicKey.toBuffer(),
+        ],
+        program.programId
+      )[0];
+
+    const scoutPda = (fan: Fan) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("scout"), fan.kp.publicKey.toBuffer()],
+        program.programId
+      )[0];
+
+    const signalPda = (market: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("signal"), market.toBuffer()],
+        program.programId
+      )[0];
+
+    const fetchPool = () =>
+      program.account.liquidityPool.fetch(liquidityPoolPda);
+
+    // Sponsor-backed YES/NO market on the shared pool, open for trading now
+    // unless `startsIn` says otherwise.
+    const createTradingMarket = async (
+      params: {
+        tradingSeconds?: number;
+        startsIn?: number;
+        resolutionSeconds?: number;
+        signalOracle?: PublicKey;
+        marketKind?: number;
+        feeBps?: number;
+        outcomeCount?: number;
+        scalarLower?: anchor.BN;
+        scalarUpper?: anchor.BN;
+        minReputation?: number;
+        earlyAccessStartsIn?: number;
+        earlyAccessMinReputation?: number;
+      } = {}
+    ): Promise<PublicKey> => {
+      const { nextMarketId } = await program.account.sponsorMarketCounter.fetch(
+        marketCounterPda
+      );
+      const [marketPda] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("market"),
+          sponsorPda.toBuffer(),
+          nextMarketId.toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
+      const now = await chainTime();
+      const tradingEndsAt = now + (params.tradingSeconds ?? 3600);
+
+      await program.methods
+        .createMarket(
+          nextMarketId,
+          Keypair.generate().publicKey,
+          hash32(`artist-${nextMarketId.toString()}`),
+          new anchor.BN(now + (params.startsIn ?? -600)),
+          new anchor.BN(tradingEndsAt),
+          new anchor.BN(tradingEndsAt + (params.resolutionSeconds ?? 86400)),
+          5000,
+          usdc(1_000),
+          params.signalOracle ?? PublicKey.default,
+          100,
+          params.minReputation != null
+            ? new anchor.BN(params.minReputation)
+            : null,
+          params.earlyAccessStartsIn != null
+            ? new anchor.BN(now + params.earlyAccessStartsIn)
+            : null,
+          new anchor.BN(params.earlyAccessMinReputation ?? 0),
+          params.marketKind ?? 0,
+          params.feeBps ?? 0,
+          params.outcomeCount ?? 2,
+          params.scalarLower ?? new anchor.BN(0),
+          params.scalarUpper ?? new anchor.BN(0)
+        )
+        .accounts({
+          config: configPda,
+          authority: provider.wallet.publicKey,
+          sponsor: sponsorPda,
+          marketCounter: marketCounterPda,
+          market: marketPda,
+          liquidityPool: liquidityPoolPda,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+      return marketPda;
+    };
+
+    const tradeAccounts = (fan: Fan, market: PublicKey) => ({
+      config: configPda,
+      user: fan.kp.publicKey,
+      mint: usdcMint,
+      market,
+      liquidityPool: liquidityPoolPda,
+      liquidityVault,
+      userTokenAccount: fan.usdcAccount,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+    });
+
+    const openPosition = (
+      fan: Fan,
+      market: PublicKey,
+      outcome: number,
+      amount: anchor.BN,
+      minSharesOut = new anchor.BN(0),
+      scoutRegistry: PublicKey | null = null
+    ) =>
+      program.methods
+        .openPosition(amount, outcome, minSharesOut, ORDER_EXPIRY)
+        .accounts({
+          ...tradeAccounts(fan, market),
+          position: positionPda(market, fan),
+          scoutRegistry,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([fan.kp])
+        .rpc();
+
+    const resolveMarket = (
+      market: PublicKey,
+      outcome: number,
+      liquidityPool: PublicKey | null = null
+    ) =>
+      program.methods
+        .resolveMarket(outcome)
+        .accounts({
+          config: configPda,
+          authority: provider.wallet.publicKey,
+          sponsor: sponsorPda,
+          market,
+          signalCommit: signalPda(market),
+          liquidityPool,
+        })
+        .rpc();
+
+    // Settles (or refunds) every fan's position; fans without a scout
+    // registry are passed their uninitialized registry address.
+    const crank = (market: PublicKey, fans: Fan[]) =>
+      program.methods
+        .distributePayouts()
+        .accounts({
+          config: configPda,
+          cranker: provider.wallet.publicKey,
+          mint: usdcMint,
+          market,
+          liquidityPool: liquidityPoolPda,
+          liquidityVault,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts(
+          fans.reduce(
+            (metas, fan) =>
+              metas.concat([
+                {
+                  pubkey: positionPda(market, fan),
+                  isSigner: false,
+                  isWritable: true,
+                },
+                { pubkey: fan.usdcAccount, isSigner: false, isWritable: true },
+                { pubkey: scoutPda(fan), isSigner: false, isWritable: true },
+              ]),
+            [] as anchor.web3.AccountMeta[]
+          )
+        )
+        .rpc();
+
+    const initScout = (fan: Fan) =>
+      program.methods
+        .initScout()
+        .accounts({
+          config: configPda,
+          payer: provider.wallet.publicKey,
+          user: fan.kp.publicKey,
+          scoutRegistry: scoutPda(fan),
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+    const closePosition = (fan: Fan, market: PublicKey) =>
+      program.methods
+        .closePosition()
+        .accounts({
+          ...tradeAccounts(fan, market),
+          position: positionPda(market, fan),
+          scoutRegistry: scoutPda(fan),
+        })
+        .signers([fan.kp])
+        .rpc();
+
+    const updateReputation = (fan: Fan, delta: number) =>
+      program.methods
+        .updateReputation(new anchor.BN(delta))
+        .accounts({
+          config: configPda,
+          admin: provider.wallet.publicKey,
+          user: fan.kp.publicKey,
+          scoutRegistry: scoutPda(fan),
+        })
+        .rpc();
+
+    const outcomeMints = (market: PublicKey) =>
+      ["yes_mint", "no_mint"].map(
+        (seed) =>
+          PublicKey.findProgramAddressSync(
+            [Buffer.from(seed), market.toBuffer()],
+            program.programId
+          )[0]
+      );
+
+    const buyOutcomeTokens = (
+      fan: Fan,
+      market: PublicKey,
+      outcomeMint: PublicKey,
+      sideYes: boolean,
+      amount: anchor.BN
+    ) =>
+      program.methods
+        .buyOutcomeTokens(amount, sideYes, new anchor.BN(0), ORDER_EXPIRY)
+        .accounts({
+          ...tradeAccounts(fan, market),
+          outcomeMint,
+          userOutcomeAccount: anchor.utils.token.associatedAddress({
+            mint: outcomeMint,
+            owner: fan.kp.publicKey,
+          }),
+          scoutRegistry: null,
+        })
+        .signers([fan.kp])
+        .rpc();
+
+    const redeemOutcomeTokens = (
+      fan: Fan,
+      market: PublicKey,
+      outcomeMint: PublicKey,
+      amount: anchor.BN
+    ) =>
+      program.methods
+        .redeemOutcomeTokens(amount)
+        .accounts({
+          ...tradeAccounts(fan, market),
+          outcomeMint,
+          userOutcomeAccount: anchor.utils.token.associatedAddress({
+            mint: outcomeMint,
+            owner: fan.kp.publicKey,
+          }),
+        })
+        .signers([fan.kp])
+        .rpc();
+
+    const claimWinnings = (fan: Fan, market: PublicKey) =>
+      program.methods
+        .claimWinnings()
+        .accounts({
+          ...tradeAccounts(fan, market),
+          position: positionPda(market, fan),
+          scoutRegistry: scoutPda(fan),
+        })
+        .signers([fan.kp])
+        .rpc();
+
+    before(async () => {
+      liquidityVault = anchor.utils.token.associatedAddress({
+        mint: usdcMint,
+        owner: liquidityPoolPda,
+      });
+      const sponsorTokenAccount = await createAta(
+        usdcMint,
+        provider.wallet.publicKey
+      );
+      await mintTo(usdcMint, sponsorTokenAccount, usdc(100_000));
+
+      // Only the first position of at least 10 USDC on each market is an
+      // early supporter.
+      await program.methods
+        .fundPool(usdc(100_000), 1, 1_000, usdc(10))
+        .accounts({
+          config: configPda,
+          authority: provider.wallet.publicKey,
+          mint: usdcMint,
+          sponsor: sponsorPda,
+          liquidityPool: liquidityPoolPda,
+          liquidityVault,
+          sponsorTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+    });
+
+    const adjustAccounts = (fan: Fan, market: PublicKey) => ({
+      ...tradeAccounts(fan, market),
+      position: positionPda(market, fan),
+      scoutRegistry: null,
+    });
+
+    const yesPrice = async (market: PublicKey) =>
+      (await program.account.market.fetch(market)).impliedProbsBps[0];
+
+    it("only closes a position while trading is open or once resolved", async () => {
+      const fan = await newFan(1_000);
+      await initScout(fan);
+      const market = await createTradingMarket({ tradingSeconds: 10 });
+      await openPosition(fan, market, YES, usdc(100));
+      await waitForTradingToClose(market);
+
+      // Stakes are committed from the end of trading until resolution.
+      await expectError(closePosition(fan, market), "TradingClosed");
+      await program.methods
+        .lockMarket()
+        .accounts({
+          config: configPda,
+          authority: provider.wallet.publicKey,
+          sponsor: sponsorPda,
+          market,
+        })
+        .rpc();
+      await expectError(closePosition(fan, market), "InvalidStatus");
+
+      await resolveMarket(market, NO);
+      await closePosition(fan, market);
+      assert.isNull(
+        await program.account.position.fetchNullable(positionPda(market, fan))
+      );
+      assert.isTrue((await balance(fan.usdcAccount)).eq(usdc(900)));
+    });
+
+    it("voids a market mid-trading and refunds every stake in full", async () => {
+      const yesFan = await newFan(1_000);
+      const noFan = await newFan(1_000);
+      const market = await createTradingMarket();
+      const lockedBefore = (await fetchPool()).locked;
+
+      await openPosition(yesFan, market, YES, usdc(300));
+      await openPosition(noFan, market, NO, usdc(100));
+      const { poolExposure } = await program.account.market.fetch(market);
+      assert.isTrue(poolExposure.gtn(0));
+      assert.isTrue(
+        (await fetchPool()).locked.eq(lockedBefore.add(poolExposure))
+      );
+
+      await expectError(
+        resolveMarket(market, INVALID),
+        "LiquidityPoolRequired"
+      );
+      await resolveMarket(market, INVALID, liquidityPoolPda);
+      const voided = await program.account.market.fetch(market);
+      assert.equal(voided.status, CANCELLED);
+      assert.equal(voided.outcome, INVALID);
+      assert.isTrue(voided.poolExposure.isZero());
+      assert.isTrue((await fetchPool()).locked.eq(lockedBefore));
+
+      await crank(market, [yesFan, noFan]);
+      assert.isTrue((await balance(yesFan.usdcAccount)).eq(usdc(1_000)));
+      assert.isTrue((await balance(noFan.usdcAccount)).eq(usdc(1_000)));
+    });
+
+    it("only lets the oracle void a market once trading has closed", async () => {
+      const fan = await newFan(1_000);
+      const oracle = Keypair.generate();
+      const sponsorResolved = await createTradingMarket({ tradingSeconds: 10 });
+      const oracleResolved = await createTradingMarket({
+        tradingSeconds: 10,
+        signalOracle: oracle.publicKey,
+      });
+      await openPosition(fan, oracleResolved, YES, usdc(100));
+      await waitForTradingToClose(oracleResolved);
+
+      await expectError(
+        resolveMarket(sponsorResolved, INVALID, liquidityPoolPda),
+        "TradingClosed"
+      );
+
+      await program.methods
+        .voidMarketByOracle()
+        .accounts({
+          config: configPda,
+          oracle: oracle.publicKey,
+          market: oracleResolved,
+          liquidityPool: liquidityPoolPda,
+        })
+        .signers([oracle])
+        .rpc();
+      const voided = await program.account.market.fetch(oracleResolved);
+      assert.equal(voided.status, CANCELLED);
+      assert.isTrue(voided.poolExposure.isZero());
+
+      await crank(oracleResolved, [fan]);
+      assert.isTrue((await balance(fan.usdcAccount)).eq(usdc(1_000)));
+    });
+
+    it("prices trades along the LMSR curve and enforces slippage", async () => {
+      const [early, follower, late] = [
+        await newFan(1_000),
+        await newFan(1_000),
+        await newFan(1_000),
+      ];
+      const market = await createTradingMarket();
+      const opening = await program.account.market.fetch(market);
+      assert.deepEqual(opening.impliedProbsBps.slice(0, 2), [5_000, 5_000]);
+
+      await openPosition(early, market, YES, usdc(200));
+      const first = await program.account.position.fetch(
+        positionPda(market, early)
+      );
+      const [yes, no] = (await program.account.market.fetch(market))
+        .impliedProbsBps;
+      assert.isTrue(first.shares.gt(usdc(200)));
+      assert.isAbove(yes, 5_000);
+      assert.approximately(yes + no, 10_000, 2);
+
+      // The same stake buys fewer shares once the price has moved.
+      await openPosition(follower, market, YES, usdc(200));
+      const second = await program.account.position.fetch(
+        positionPda(market, follower)
+      );
+      assert.isTrue(second.shares.lt(first.shares));
+      assert.isAbove(await yesPrice(market), yes);
+
+      await expectError(
+        openPosition(late, market, YES, usdc(200), second.shares),
+        "SlippageExceeded"
+      );
+      const beforeNo = await yesPrice(market);
+      await openPosition(late, market, NO, usdc(400));
+      assert.isBelow(await yesPrice(market), beforeNo);
+    });
+
+    it("tops up, partially exits and closes a position before resolution", async () => {
+      const fan = await newFan(1_000);
+      const market = await createTradingMarket();
+      await openPosition(fan, market, YES, usdc(100));
+      const opened = await program.account.position.fetch(
+        positionPda(market, fan)
+      );
+      // The opening stake is an early supporter's and carries the bonus.
+      assert.isTrue(opened.earlySupporter);
+      assert.isTrue(opened.winnings.gt(opened.shares.sub(opened.amount)));
+
+      // Top-ups are priced like any other late entry.
+      await program.methods
+        .increasePosition(usdc(50), new anchor.BN(0), ORDER_EXPIRY)
+        .accounts(adjustAccounts(fan, market))
+        .signers([fan.kp])
+        .rpc();
+      const increased = await program.account.position.fetch(
+        positionPda(market, fan)
+      );
+      assert.isTrue(increased.amount.eq(usdc(150)));
+      const addedShares = increased.shares.sub(opened.shares);
+      assert.isTrue(
+        increased.winnings.sub(opened.winnings).eq(addedShares.sub(usdc(50)))
+      );
+
+      await expectError(
+        program.methods
+          .decreasePosition(usdc(150), new anchor.BN(0))
+          .accounts(adjustAccounts(fan, market))
+          .signers([fan.kp])
+          .rpc(),
+        "InvalidAmount"
+      );
+      const beforeDecrease = await balance(fan.usdcAccount);
+      await program.methods
+        .decreasePosition(usdc(60), new anchor.BN(0))
+        .accounts(adjustAccounts(fan, market))
+        .signers([fan.kp])
+        .rpc();
+      const refund = (await balance(fan.usdcAccount)).sub(beforeDecrease);
+      assert.isTrue(refund.gtn(0));
+      assert.isTrue(refund.lte(usdc(60)));
+      const decreased = await program.account.position.fetch(
+        positionPda(market, fan)
+      );
+      assert.isTrue(decreased.amount.eq(usdc(90)));
+
+      await closePosition(fan, market);
+      assert.isNull(
+        await program.account.position.fetchNullable(positionPda(market, fan))
+      );
+      // Selling back never returns more than was paid in.
+      assert.isTrue((await balance(fan.usdcAccount)).lte(usdc(1_000)));
+      const emptied = await program.account.market.fetch(market);
+      assert.isTrue(emptied.outcomeShares[0].isZero());
+      assert.isTrue(emptied.poolExposure.isZero());
+    });
+
+    it("settles a resolved market through the payout crank", async () => {
+      const winner = await newFan(1_000);
+      const loser = await newFan(1_000);
+      await initScout(winner);
+      const market = await createTradingMarket({ tradingSeconds: 15 });
+      await openPosition(winner, market, YES, usdc(100));
+      await openPosition(loser, market, NO, usdc(100));
+      const won = await program.account.position.fetch(
+        positionPda(market, winner)
+      );
+
+      await waitForTradingToClose(market);
+      await resolveMarket(market, YES);
+      assert.equal(
+        (await program.account.market.fetch(market)).status,
+        RESOLVED
+      );
+
+      // The loser never created a scout registry and is still settled.
+      await crank(market, [winner, loser]);
+      const paid = usdc(900).add(won.amount).add(won.winnings);
+      assert.isTrue((await balance(winner.usdcAccount)).eq(paid));
+      assert.isTrue((await balance(loser.usdcAccount)).eq(usdc(900)));
+      for (const fan of [winner, loser]) {
+        const position = await program.account.position.fetch(
+          positionPda(market, fan)
+        );
+        assert.isTrue(position.settled);
+      }
+      const registry = await program.account.scoutRegistry.fetch(
+        scoutPda(winner)
+      );
+      assert.equal(registry.wins, 1);
+
+      // Settled positions are skipped, so re-running the crank pays nothing.
+      await crank(market, [loser, winner]);
+      assert.isTrue((await balance(winner.usdcAccount)).eq(paid));
+      assert.isTrue((await balance(loser.usdcAccount)).eq(usdc(900)));
+    });
+
+    it("splits a parimutuel pot by stake and pays the dust to the last winner", async () => {
+      const loser = await newFan(1_000);
+      const small = await newFan(1_000);
+      const large = await newFan(1_000);
+      const market = await createTradingMarket({
+        tradingSeconds: 15,
+        marketKind: 1,
+        feeBps: 500,
+      });
+      // The loser enters first, so neither winner is an early supporter.
+      const smallStake = new anchor.BN(33_333_333);
+      const largeStake = new anchor.BN(66_666_667);
+      await openPosition(loser, market, NO, usdc(100));
+      await openPosition(small, market, YES, smallStake);
+      await openPosition(large, market, YES, largeStake);
+
+      await waitForTradingToClose(market);
+      await resolveMarket(market, YES);
+      const capacityBefore = (await fetchPool()).capacity;
+      await crank(market, [small, large, loser]);
+
+      // 5% of the losing side goes to the sponsor; the winners split the
+      // other 195 USDC by stake and `large` settles last.
+      const smallPayout = new anchor.BN(64_999_999);
+      const largePayout = usdc(195).sub(smallPayout);
+      assert.isTrue(
+        (await balance(small.usdcAccount)).eq(
+          usdc(1_000).sub(smallStake).add(smallPayout)
+        )
+      );
+      assert.isTrue(
+        (await balance(large.usdcAccount)).eq(
+          usdc(1_000).sub(largeStake).add(largePayout)
+        )
+      );
+      assert.isTrue((await balance(loser.usdcAccount)).eq(usdc(900)));
+      assert.isTrue(
+        (await fetchPool()).capacity.eq(capacityBefore.add(usdc(5)))
+      );
+    });
+
+    it("mints outcome tokens and redeems the winning side one for one", async () => {
+      const yesFan = await newFan(1_000);
+      const noFan = await newFan(1_000);
+      const market = await createTradingMarket({ tradingSeconds: 20 });
+      const [yesMint, noMint] = outcomeMints(market);
+      await program.methods
+        .createOutcomeMints()
+        .accounts({
+          config: configPda,
+          authority: provider.wallet.publicKey,
+          sponsor: sponsorPda,
+          market,
+          mint: usdcMint,
+          yesMint,
+          noMint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+      const yesTokens = await createAta(yesMint, yesFan.kp.publicKey);
+      const noTokens = await createAta(noMint, noFan.kp.publicKey);
+
+      await buyOutcomeTokens(yesFan, market, yesMint, true, usdc(100));
+      await buyOutcomeTokens(noFan, market, noMint, false, usdc(50));
+      const yesShares = await balance(yesTokens);
+      const noShares = await balance(noTokens);
+      assert.isTrue(yesShares.gt(usdc(100)));
+      assert.isTrue(noShares.gt(usdc(50)));
+      // Tokens are held in the wallet rather than in a position account.
+      assert.isNull(
+        await program.account.position.fetchNullable(
+          positionPda(market, yesFan)
+        )
+      );
+
+      await waitForTradingToClose(market);
+      await resolveMarket(market, YES);
+      await redeemOutcomeTokens(yesFan, market, yesMint, yesShares);
+      assert.isTrue((await balance(yesTokens)).isZero());
+      assert.isTrue(
+        (await balance(yesFan.usdcAccount)).eq(usdc(900).add(yesShares))
+      );
+      await expectError(
+        redeemOutcomeTokens(noFan, market, noMint, noShares),
+        "InvalidOutcomeMint"
+      );
+    });
+
+    it("resolves a categorical market to one of its outcomes", async () => {
+      const fans = [
+        await newFan(1_000),
+        await newFan(1_000),
+        await newFan(1_000),
+      ];
+      for (const fan of fans) await initScout(fan);
+      const market = await createTradingMarket({
+        tradingSeconds: 15,
+        outcomeCount: 3,
+      });
+      const opening = await program.account.market.fetch(market);
+      assert.deepEqual(
+        opening.impliedProbsBps.slice(0, 3),
+        [3_333, 3_333, 3_333]
+      );
+      for (let i = 0; i < fans.length; i++) {
+        await openPosition(fans[i], market, i + 1, usdc(100));
+      }
+      const winning = await program.account.position.fetch(
+        positionPda(market, fans[1])
+      );
+
+      await waitForTradingToClose(market);
+      await expectError(resolveMarket(market, 4), "InvalidOutcome");
+      await resolveMarket(market, 2);
+      assert.equal(
+        (await program.account.market.fetch(market)).status,
+        RESOLVED
+      );
+
+      for (const fan of fans) await claimWinnings(fan, market);
+      assert.isTrue(
+        (await balance(fans[1].usdcAccount)).eq(
+          usdc(900).add(winning.amount).add(winning.winnings)
+        )
+      );
+      for (const loser of [fans[0], fans[2]]) {
+        assert.isTrue((await balance(loser.usdcAccount)).eq(usdc(900)));
+        assert.isNull(
+          await program.account.position.fetchNullable(
+            positionPda(market, loser)
+          )
+        );
+      }
+    });
+
+    it("pays scalar LONG and SHORT holders their linear share", async () => {
+      const long = await newFan(1_000);
+      const short = await newFan(1_000);
+      const oracle = Keypair.generate();
+      const market = await createTradingMarket({
+        tradingSeconds: 15,
+        signalOracle: oracle.publicKey,
+        scalarLower: new anchor.BN(0),
+        scalarUpper: new anchor.BN(1_000),
+      });
+      await openPosition(long, market, YES, usdc(100));
+      await openPosition(short, market, NO, usdc(100));
+      const [longPosition, shortPosition] = await Promise.all(
+        [long, short].map((fan) =>
+          program.account.position.fetch(positionPda(market, fan))
+        )
+      );
+
+      await waitForTradingToClose(market);
+      await program.methods
+        .resolveScalarMarket(new anchor.BN(250))
+        .accounts({
+          config: configPda,
+          oracle: oracle.publicKey,
+          market,
+          liquidityPool: null,
+        })
+        .signers([oracle])
+        .rpc();
+      const resolved = await program.account.market.fetch(market);
+      assert.isTrue(resolved.scalarValue.eqn(250));
+      assert.equal(resolved.longPayoutBps, 2_500);
+
+      await crank(market, [long, short]);
+      const share = (
+        position: { amount: anchor.BN; winnings: anchor.BN },
+        bps: number
+      ) => position.amount.add(position.winnings).muln(bps).divn(10_000);
+      assert.isTrue(
+        (await balance(long.usdcAccount)).eq(
+          usdc(900).add(share(longPosition, 2_500))
+        )
+      );
+      assert.isTrue(
+        (await balance(short.usdcAccount)).eq(
+          usdc(900).add(share(shortPosition, 7_500))
+        )
+      );
+    });
+
+    it("only lets a sponsor back markets with its own pool", async () => {
+      const rival = (await newFan(0)).kp;
+      const [rivalSponsor] = PublicKey.findProgramAddressSync(
+        [Buffer.from("sponsor"), rival.publicKey.toBuffer()],
+        program.programId
+      );
+      const [rivalCounter] = PublicKey.findProgramAddressSync(
+        [Buffer.from("market_counter"), rivalSponsor.toBuffer()],
+        program.programId
+      );
+      const [rivalPool] = PublicKey.findProgramAddressSync(
+        [Buffer.from("liquidity_pool"), rivalSponsor.toBuffer()],
+        program.programId
+      );
+      await program.methods
+        .registerSponsor(hash32("Rival Label"))
+        .accounts({
+          config: configPda,
+          authority: rival.publicKey,
+          sponsor: rivalSponsor,
+          marketCounter: rivalCounter,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([rival])
+        .rpc();
+
+      const marketId = new anchor.BN(1);
+      const [market] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("market"),
+          rivalSponsor.toBuffer(),
+          marketId.toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
+      const now = await chainTime();
+      const createOn = (liquidityPool: PublicKey) =>
+        program.methods
+          .createMarket(
+            marketId,
+            Keypair.generate().publicKey,
+            hash32("rival-artist"),
+            new anchor.BN(now - 600),
+            new anchor.BN(now + 3600),
+            new anchor.BN(now + 90000),
+            5000,
+            usdc(100_000),
+            PublicKey.default,
+            0,
+            null,
+            null,
+            new anchor.BN(0),
+            0,
+            0,
+            2,
+            new anchor.BN(0),
+            new anchor.BN(0)
+          )
+          .accounts({
+            config: configPda,
+            authority: rival.publicKey,
+            sponsor: rivalSponsor,
+            marketCounter: rivalCounter,
+            market,
+            liquidityPool,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([rival])
+          .rpc();
+
+      await expectError(createOn(liquidityPoolPda), "ConstraintSeeds");
+      await createOn(rivalPool);
+      const created = await program.account.market.fetch(market);
+      assert.isTrue(created.liquidityPool.equals(rivalPool));
+    });
+
+    it("lets anyone void a market nobody resolved by its deadline", async () => {
+      const fan = await newFan(1_000);
+      const caller = await newFan(0);
+      const market = await createTradingMarket({
+        tradingSeconds: 5,
+        resolutionSeconds: 5,
+      });
+      const lockedBefore = (await fetchPool()).locked;
+      await openPosition(fan, market, YES, usdc(100));
+      const voidExpired = () =>
+        program.methods
+          .voidExpiredMarket()
+          .accounts({
+            config: configPda,
+            caller: caller.kp.publicKey,
+            market,
+            liquidityPool: liquidityPoolPda,
+          })
+          .signers([caller.kp])
+          .rpc();
+
+      await expectError(voidExpired(), "ResolutionDeadlineNotReached");
+      const { resolutionDeadline } = await program.account.market.fetch(market);
+      await waitUntilAfter(resolutionDeadline);
+      await expectError(resolveMarket(market, YES), "ResolutionDeadlinePassed");
+
+      await voidExpired();
+      const voided = await program.account.market.fetch(market);
+      assert.equal(voided.status, CANCELLED);
+      assert.isTrue(voided.poolExposure.isZero());
+      assert.isTrue((await fetchPool()).locked.eq(lockedBefore));
+
+      await crank(market, [fan]);
+      assert.isTrue((await balance(fan.usdcAccount)).eq(usdc(1_000)));
+    });
+
+    it("pays the parimutuel early-supporter bonus on the opening stake only", async () => {
+      const early = await newFan(1_000);
+      const loser = await newFan(1_000);
+      const market = await createTradingMarket({
+        tradingSeconds: 15,
+        marketKind: 1,
+      });
+      await openPosition(early, market, YES, usdc(100));
+      await openPosition(loser, market, NO, usdc(100));
+      await program.methods
+        .increasePosition(usdc(100), new anchor.BN(0), ORDER_EXPIRY)
+        .accounts(adjustAccounts(early, market))
+        .signers([early.kp])
+        .rpc();
+      const position = await program.account.position.fetch(
+        positionPda(market, early)
+      );
+      assert.isTrue(position.earlyShares.eq(usdc(100)));
+
+      await waitForTradingToClose(market);
+      await resolveMarket(market, YES);
+      await crank(market, [early, loser]);
+      // The whole 300 USDC pot, plus 10% on the 50 USDC won by the opening
+      // stake; the top-up's winnings earn no bonus.
+      assert.isTrue(
+        (await balance(early.usdcAccount)).eq(usdc(800 + 300 + 5))
+      );
+    });
+
+    it("resolves a market with a signal commitment only by proof", async () => {
+      const fan = await newFan(1_000);
+      const market = await createTradingMarket({ tradingSeconds: 10 });
+      await openPosition(fan, market, YES, usdc(100));
+      const { artistWallet } = await program.account.market.fetch(market);
+
+      // Two datapoints for the artist; only the committed metric resolves.
+      const sha256 = (...parts: Buffer[]) =>
+        createHash("sha256").update(Buffer.concat(parts)).digest();
+      const leaf = (metric: number[], value: anchor.BN) =>
+        sha256(
+          Buffer.from([0]),
+          market.toBuffer(),
+          artistWallet.toBuffer(),
+          Buffer.from(metric),
+          value.toArrayLike(Buffer, "le", 8)
+        );
+      const streams = hash32("streams-30d");
+      const followers = hash32("followers-30d");
+      const streamsBps = new anchor.BN(7_500);
+      const followersBps = new anchor.BN(1_000);
+      const streamsLeaf = leaf(streams, streamsBps);
+      const followersLeaf = leaf(followers, followersBps);
+      const [low, high] = [streamsLeaf, followersLeaf].sort(Buffer.compare);
+      const root = sha256(Buffer.from([1]), low, high);
+
+      await program.methods
+        .submitSignalCommitment(Array.from(root), streams)
+        .accounts({
+          config: configPda,
+          authority: provider.wallet.publicKey,
+          sponsor: sponsorPda,
+          market,
+          signalCommit: signalPda(market),
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+      await waitForTradingToClose(market);
+
+      // The sponsor can no longer pick the outcome by hand.
+      await expectError(resolveMarket(market, NO), "ProofResolutionRequired");
+      const resolveWithProof = (value: anchor.BN, sibling: Buffer) =>
+        program.methods
+          .resolveMarketWithProof(value, [Array.from(sibling)])
+          .accounts({
+            config: configPda,
+            resolver: fan.kp.publicKey,
+            market,
+            signalCommit: signalPda(market),
+          })
+          .signers([fan.kp])
+          .rpc();
+      await expectError(
+        resolveWithProof(followersBps, streamsLeaf),
+        "InvalidSignalProof"
+      );
+      await resolveWithProof(streamsBps, followersLeaf);
+      const resolved = await program.account.market.fetch(market);
+      assert.equal(resolved.status, RESOLVED);
+      assert.equal(resolved.outcome, YES);
+      assert.isTrue(resolved.measuredBps.eq(streamsBps));
+    });
+
+    it("keeps early-supporter slots for stakes above the minimum", async () => {
+      const dust = await newFan(1_000);
+      const fan = await newFan(1_000);
+      const market = await createTradingMarket();
+      await openPosition(dust, market, YES, new anchor.BN(1));
+      await openPosition(fan, market, YES, usdc(10));
+
+      const dusted = await program.account.position.fetch(
+        positionPda(market, dust)
+      );
+      const backed = await program.account.position.fetch(
+        positionPda(market, fan)
+      );
+      assert.isFalse(dusted.earlySupporter);
+      assert.isTrue(backed.earlySupporter);
+      assert.equal(
+        (await program.account.market.fetch(market)).earlySupporterCount,
+        1
+      );
+    });
+
+    it("lets holders without a scout registry claim and close", async () => {
+      const winner = await newFan(1_000);
+      const loser = await newFan(1_000);
+      const market = await createTradingMarket({ tradingSeconds: 10 });
+      await openPosition(winner, market, YES, usdc(100));
+      await openPosition(loser, market, NO, usdc(100));
+      const won = await program.account.position.fetch(
+        positionPda(market, winner)
+      );
+
+      await waitForTradingToClose(market);
+      await resolveMarket(market, YES);
+      await claimWinnings(winner, market);
+      await closePosition(loser, market);
+      assert.isTrue(
+        (await balance(winner.usdcAccount)).eq(
+          usdc(900).add(won.amount).add(won.winnings)
+        )
+      );
+      assert.isTrue((await balance(loser.usdcAccount)).eq(usdc(900)));
+      assert.isNull(
+        await program.account.position.fetchNullable(
+          positionPda(market, loser)
+        )
+      );
+      assert.isNull(
+        await program.account.scoutRegistry.fetchNullable(scoutPda(winner))
+      );
+    });
+
+    it("decays scout scores each season at a rate fixed up front", async () => {
+      const admin = { config: configPda, admin: provider.wallet.publicKey };
+      const setSeasonDecay = (decayBps: number) =>
+        program.methods.setSeasonDecay(decayBps).accounts(admin).rpc();
+      const startSeason = () =>
+        program.methods.startSeason().accounts(admin).rpc();
+      const fan = await newFan(0);
+      await initScout(fan);
+      await updateReputation(fan, 1_000);
+
+      const { currentSeason } = await program.account.superfanConfig.fetch(
+        configPda
+      );
+      assert.equal(currentSeason, 0);
+      await setSeasonDecay(5_000);
+      await startSeason();
+      await expectError(setSeasonDecay(0), "SeasonDecayLocked");
+
+      // The registry rolls into season 1 on its next update: half the score
+      // is kept and season 0's points are snapshotted.
+      await updateReputation(fan, 200);
+      let registry = await program.account.scoutRegistry.fetch(scoutPda(fan));
+      assert.equal(registry.season, 1);
+      assert.isTrue(registry.score.eqn(700));
+      assert.isTrue(registry.seasonScore.eqn(200));
+      assert.isTrue(registry.lastSeasonScore.eqn(1_000));
+
+      await startSeason();
+      await program.methods
+        .rollScoutSeason()
+        .accounts({ config: configPda, scoutRegistry: scoutPda(fan) })
+        .rpc();
+      registry = await program.account.scoutRegistry.fetch(scoutPda(fan));
+      assert.equal(registry.season, 2);
+      assert.isTrue(registry.score.eqn(350));
+      assert.isTrue(registry.seasonScore.isZero());
+      assert.isTrue(registry.lastSeasonScore.eqn(200));
+    });
+
+    it("only takes new stakes inside the trading window", async () => {
+      const fan = await newFan(1_000);
+      const upcoming = await createTradingMarket({ startsIn: 1_800 });
+      await expectError(
+        openPosition(fan, upcoming, YES, usdc(100)),
+        "TradingNotStarted"
+      );
+
+      const closing = await createTradingMarket({ tradingSeconds: 5 });
+      await openPosition(fan, closing, YES, usdc(100));
+      await waitForTradingToClose(closing);
+      await expectError(
+        program.methods
+          .increasePosition(usdc(50), new anchor.BN(0), ORDER_EXPIRY)
+          .accounts(adjustAccounts(fan, closing))
+          .signers([fan.kp])
+          .rpc(),
+        "TradingClosed"
+      );
+    });
+    it("only lets the sponsor withdraw capital not backing open positions", async () => {
+      const sponsorTokenAccount = anchor.utils.token.associatedAddress({
+        mint: usdcMint,
+        owner: provider.wallet.publicKey,
+      });
+      const withdrawPool = (amount: anchor.BN) =>
+        program.methods
+          .withdrawPool(amount)
+          .accounts({
+            config: configPda,
+            authority: provider.wallet.publicKey,
+            mint: usdcMint,
+            sponsor: sponsorPda,
+            liquidityPool: liquidityPoolPda,
+            liquidityVault,
+            sponsorTokenAccount,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+          })
+          .rpc();
+      const fan = await newFan(1_000);
+      const market = await createTradingMarket();
+      await openPosition(fan, market, YES, usdc(100));
+
+      const pool = await fetchPool();
+      assert.isTrue(pool.locked.gtn(0));
+      const available = pool.capacity
+        .sub(pool.locked)
+        .sub(pool.reputationBonusBudget);
+      await expectError(
+        withdrawPool(available.addn(1)),
+        "InsufficientLiquidity"
+      );
+
+      const walletBefore = await balance(sponsorTokenAccount);
+      await withdrawPool(usdc(1));
+      assert.isTrue(
+        (await fetchPool()).capacity.eq(pool.capacity.sub(usdc(1)))
+      );
+      assert.isTrue(
+        (await balance(sponsorTokenAccount)).eq(walletBefore.add(usdc(1)))
+      );
+    });
+    it("moves an open position to another wallet", async () => {
+      const seller = await newFan(1_000);
+      const buyer = await newFan(0);
+      const market = await createTradingMarket();
+      await openPosition(seller, market, YES, usdc(100));
+      const sold = await program.account.position.fetch(
+        positionPda(market, seller)
+      );
+
+      await program.methods
+        .transferPosition()
+        .accounts({
+          config: configPda,
+          user: seller.kp.publicKey,
+          recipient: buyer.kp.publicKey,
+          market,
+          position: positionPda(market, seller),
+          recipientPosition: positionPda(market, buyer),
+          recipientScoutRegistry: null,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([seller.kp])
+        .rpc();
+      assert.isNull(
+        await program.account.position.fetchNullable(
+          positionPda(market, seller)
+        )
+      );
+      const moved = await program.account.position.fetch(
+        positionPda(market, buyer)
+      );
+      assert.isTrue(moved.user.equals(buyer.kp.publicKey));
+      assert.equal(moved.side, YES);
+      assert.isTrue(moved.amount.eq(sold.amount));
+      assert.isTrue(moved.shares.eq(sold.shares));
+      assert.isTrue(moved.winnings.eq(sold.winnings));
+      assert.equal(moved.createdAt.toString(), sold.createdAt.toString());
+
+      // The recipient can exit it like any position of their own.
+      await closePosition(buyer, market);
+      assert.isTrue((await balance(buyer.usdcAccount)).gtn(0));
+    });
+    it("accumulates admin reputation updates on a scout registry", async () => {
+      const fan = await newFan(0);
+      await initScout(fan);
+      let registry = await program.account.scoutRegistry.fetch(scoutPda(fan));
+      assert.isTrue(registry.user.equals(fan.kp.publicKey));
+      assert.isTrue(registry.score.isZero());
+
+      await updateReputation(fan, 300);
+      await updateReputation(fan, -120);
+      registry = await program.account.scoutRegistry.fetch(scoutPda(fan));
+      assert.isTrue(registry.score.eqn(180));
+      assert.isTrue(registry.seasonScore.eqn(180));
+
+      await expectError(
+        program.methods
+          .updateReputation(new anchor.BN(1_000))
+          .accounts({
+            config: configPda,
+            admin: fan.kp.publicKey,
+            user: fan.kp.publicKey,
+            scoutRegistry: scoutPda(fan),
+          })
+          .signers([fan.kp])
+          .rpc(),
+        "Unauthorized"
+      );
+    });
+    it("reports a scout's record and calibration", async () => {
+      const fan = await newFan(1_000);
+      await initScout(fan);
+      // Both markets open at even odds, so each call has a Brier score of
+      // 2_500 bps whichever way it goes.
+      const won = await createTradingMarket({ tradingSeconds: 10 });
+      const lost = await createTradingMarket({ tradingSeconds: 10 });
+      await openPosition(fan, won, YES, usdc(100));
+      await openPosition(fan, lost, NO, usdc(50));
+      await waitForTradingToClose(lost);
+      await resolveMarket(won, YES);
+      await resolveMarket(lost, YES);
+      await claimWinnings(fan, won);
+      await closePosition(fan, lost);
+
+      const stats = await program.methods
+        .scoutStats()
+        .accounts({ scoutRegistry: scoutPda(fan) })
+        .view();
+      assert.isTrue(stats.user.equals(fan.kp.publicKey));
+      assert.equal(stats.marketsParticipated, 2);
+      assert.equal(stats.wins, 1);
+      assert.equal(stats.losses, 1);
+      assert.isTrue(stats.cumulativeStake.eq(usdc(150)));
+      assert.equal(stats.winRateBps, 5_000);
+      assert.equal(stats.calibrationBps, 7_500);
+    });
+    it("gates reputation-only markets and early access by scout score", async () => {
+      const gated = await createTradingMarket({ minReputation: 100 });
+      const anon = await newFan(1_000);
+      await expectError(
+        openPosition(anon, gated, YES, usdc(10)),
+        "ScoutRegistryRequired"
+      );
+      const scout = await newFan(1_000);
+      await initScout(scout);
+      const enter = (market: PublicKey) =>
+        openPosition(scout, market, YES, usdc(10), undefined, scoutPda(scout));
+      await expectError(enter(gated), "ReputationTooLow");
+      await updateReputation(scout, 150);
+      await enter(gated);
+
+      // General trading opens in half an hour; scouts of 500+ may enter now.
+      const early = await createTradingMarket({
+        startsIn: 1_800,
+        earlyAccessStartsIn: -60,
+        earlyAccessMinReputation: 500,
+      });
+      await expectError(
+        openPosition(anon, early, YES, usdc(10)),
+        "ScoutRegistryRequired"
+      );
+      await expectError(enter(early), "ReputationTooLow");
+      await updateReputation(scout, 400);
+      await enter(early);
+      const position = await program.account.position.fetch(
+        positionPda(early, scout)
+      );
+      assert.isTrue(position.amount.eq(usdc(10)));
+
+      // Without an early-access window nobody enters before the start.
+      const upcoming = await createTradingMarket({ startsIn: 1_800 });
+      await expectError(enter(upcoming), "TradingNotStarted");
+    });
+    it("pays top-tier scouts a reputation bonus out of the sponsor's budget", async () => {
+      const configureBonus = (budget: anchor.BN, maxBonusBps: number) =>
+        program.methods
+          .configureReputationBonus(budget, maxBonusBps)
+          .accounts({
+            config: configPda,
+            authority: provider.wallet.publicKey,
+            sponsor: sponsorPda,
+            liquidityPool: liquidityPoolPda,
+          })
+          .rpc();
+      const pool = await fetchPool();
+      await expectError(
+        configureBonus(pool.capacity.sub(pool.locked).addn(1), 2_000),
+        "InsufficientLiquidity"
+      );
+      await configureBonus(usdc(1_000), 2_000);
+
+      const scout = await newFan(1_000);
+      await initScout(scout);
+      await updateReputation(scout, 1_000);
+      const market = await createTradingMarket({ tradingSeconds: 10 });
+      await openPosition(scout, market, YES, usdc(100));
+      const won = await program.account.position.fetch(
+        positionPda(market, scout)
+      );
+      await waitForTradingToClose(market);
+      await resolveMarket(market, YES);
+      await claimWinnings(scout, market);
+
+      // A top-tier scout gets the full 20% on top of their winnings.
+      const bonus = won.winnings.muln(2_000).divn(10_000);
+      assert.isTrue(bonus.gtn(0));
+      assert.isTrue(
+        (await balance(scout.usdcAccount)).eq(
+          usdc(900).add(won.amount).add(won.winnings).add(bonus)
+        )
+      );
+      assert.isTrue(
+        (await fetchPool()).reputationBonusBudget.eq(usdc(1_000).sub(bonus))
+      );
+      await configureBonus(new anchor.BN(0), 0);
+    });
+    it("resolves oracle markets against the conviction threshold", async () => {
+      const oracle = Keypair.generate();
+      const resolveByOracle = (
+        market: PublicKey,
+        measuredBps: number,
+        signer = oracle
+      ) =>
+        program.methods
+          .resolveMarketByOracle(new anchor.BN(measuredBps))
+          .accounts({
+            config: configPda,
+            oracle: signer.publicKey,
+            market,
+            liquidityPool: null,
+          })
+          .signers([signer])
+          .rpc();
+      // Both markets resolve YES at 5_000 bps or more.
+      const hit = await createTradingMarket({
+        tradingSeconds: 10,
+        signalOracle: oracle.publicKey,
+      });
+      const missed = await createTradingMarket({
+        tradingSeconds: 10,
+        signalOracle: oracle.publicKey,
+      });
+      await waitForTradingToClose(missed);
+
+      await expectError(resolveMarket(hit, YES), "OracleResolutionRequired");
+      await expectError(
+        resolveByOracle(hit, 9_000, Keypair.generate()),
+        "Unauthorized"
+      );
+
+      await resolveByOracle(hit, 5_000);
+      await resolveByOracle(missed, 4_999);
+      const yes = await program.account.market.fetch(hit);
+      assert.equal(yes.status, RESOLVED);
+      assert.equal(yes.outcome, YES);
+      assert.equal(yes.measuredBps.toNumber(), 5_000);
+      const no = await program.account.market.fetch(missed);
+      assert.equal(no.outcome, NO);
+      assert.equal(no.measuredBps.toNumber(), 4_999);
+    });
+    it("rejects orders submitted after their expiry", async () => {
+      const fan = await newFan(1_000);
+      const market = await createTradingMarket();
+      const expired = new anchor.BN((await chainTime()) - 60);
+      await expectError(
+        program.methods
+          .openPosition(usdc(100), YES, new anchor.BN(0), expired)
+          .accounts({
+            ...adjustAccounts(fan, market),
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([fan.kp])
+          .rpc(),
+        "OrderExpired"
+      );
+
+      await openPosition(fan, market, YES, usdc(100));
+      await expectError(
+        program.methods
+          .increasePosition(usdc(50), new anchor.BN(0), expired)
+          .accounts(adjustAccounts(fan, market))
+          .signers([fan.kp])
+          .rpc(),
+        "OrderExpired"
+      );
+      assert.isTrue((await balance(fan.usdcAccount)).eq(usdc(900)));
+    });
+  });
 });